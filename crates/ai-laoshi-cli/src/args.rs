@@ -0,0 +1,119 @@
+// NOTE: The command line arguments (parsed with clap derive).
+// Without a subcommand, `laoshi` starts the interactive chat loop.
+use ai_laoshi_core::utils::document::MAX_DOCUMENT_CHARS;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "laoshi", version, about = "Your AI laoshi in the terminal")]
+pub struct Args {
+    /// Print the responses as is (no markdown rendering)
+    #[arg(long, global = true)]
+    pub raw: bool,
+
+    #[command(subcommand)]
+    pub cmd: Option<SubCmd>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SubCmd {
+    /// Compose the instructions files and upload them to the assistant
+    Instructions {
+        /// Print the composed instructions instead of uploading them
+        #[arg(long)]
+        print: bool,
+    },
+    /// Show what `apply` would change on the remote assistant
+    Plan,
+    /// Apply the plan (update the assistant, upload the changed bundles)
+    Apply {
+        /// Apply without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Ask one question, with the piped stdin as a document
+    /// (e.g., `git diff | laoshi ask "review this"`)
+    Ask {
+        /// The question
+        question: Vec<String>,
+        /// Upload the stdin as a file when larger than --max-chars
+        /// (instead of truncating it)
+        #[arg(long)]
+        upload: bool,
+        /// The max stdin chars in the message
+        #[arg(long, default_value_t = MAX_DOCUMENT_CHARS)]
+        max_chars: usize,
+    },
+    /// Show the assistant, instructions, files and thread in use
+    Status,
+    /// Look up a word in the local CC-CEDICT, by hanzi, pinyin (tones
+    /// optional) or English (no API call)
+    Dict {
+        /// The word, pinyin or English (e.g., 学习, xuexi, "to study")
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// The max entries shown
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Full-screen chat (transcript, input box, scrolling)
+    Tui,
+    /// Inspect the layered config (global, project, env)
+    Config {
+        #[command(subcommand)]
+        cmd: ConfigCmd,
+    },
+    /// The quiz scores (see /quiz)
+    Quiz {
+        #[command(subcommand)]
+        cmd: QuizCmd,
+    },
+    /// The grammar mistakes (see /correct)
+    Grammar {
+        #[command(subcommand)]
+        cmd: GrammarCmd,
+    },
+    /// Export or import the vocabulary deck (saved with /save)
+    Vocab {
+        #[command(subcommand)]
+        cmd: VocabCmd,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCmd {
+    /// Show the merged config values and where each one comes from
+    Show,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GrammarCmd {
+    /// Show the mistakes per category and the recurring ones
+    Stats,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QuizCmd {
+    /// Show the progress per topic (attempts, last, best and average scores)
+    Stats,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VocabCmd {
+    /// Export the deck as an Anki package or TSV (TSV on stdout by default).
+    /// Re-exporting updates the Anki notes (stable ids), no duplicates.
+    Export {
+        /// The Anki package file (e.g., deck.apkg)
+        #[arg(long, conflicts_with = "tsv")]
+        anki: Option<PathBuf>,
+        /// The TSV file (with the Anki headers)
+        #[arg(long)]
+        tsv: Option<PathBuf>,
+    },
+    /// Import a TSV file (added, or updated by id or hanzi)
+    Import {
+        /// The TSV file (columns from its `#columns:` header, else
+        /// hanzi, pinyin, definition, example)
+        file: PathBuf,
+    },
+}
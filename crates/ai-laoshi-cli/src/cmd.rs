@@ -0,0 +1,965 @@
+// NOTE: The REPL slash commands registry. Each command has a name, aliases,
+// an argument spec and a help text, used to parse the user input, print
+// the /help and tab complete. Anything not starting with `/` is chat
+// (start with `//` to send a message starting with `/`).
+use crate::utils::tutor::PinyinSetting;
+use ai_laoshi_core::chinese::{Script, ToneStyle};
+use ai_laoshi_core::{PinyinDisplay, MAX_QUIZ_QUESTIONS, PROFILE_FIELDS};
+
+// region:       -- Types
+
+/// Input Command from user
+// NOTE: When a user says something, we'll map it to this Enum.
+// Our app will have a loop that constantly does this.
+#[derive(Debug)]
+pub enum Cmd {
+    Help(Option<String>),
+    Quit,
+    Chat(String),
+    RefreshAll,
+    RefreshConversation,
+    RefreshInstructions,
+    /// All the bundles, or only the named one
+    RefreshFiles(Option<String>),
+    /// Upload files for the next message (none lists the pending ones)
+    Attach(Vec<String>),
+    /// Compose the message in $EDITOR
+    Edit,
+    /// Assistant, instructions, files, thread and token usage
+    Status,
+    /// Set the pinyin display or tones (none shows the current ones)
+    Pinyin(Option<PinyinSetting>),
+    /// Save words to the vocabulary deck (none, the new words of the last answer)
+    Save(Vec<String>),
+    /// Review the due words of the vocabulary deck
+    Review,
+    /// The HSK levels of the text (the last answer if empty)
+    Level(String),
+    /// A quiz on the file bundles (all topics if none)
+    Quiz {
+        topic: Option<String>,
+        num_questions: usize,
+    },
+    /// Convert the responses to a script (`Some(None)` is off, none shows
+    /// the current one)
+    Script(Option<Option<Script>>),
+    /// Look up the local dictionary (hanzi, pinyin or English)
+    Dict(String),
+    /// Turn the grammar correction on or off (none shows the mode)
+    Correct(Option<bool>),
+    /// Show, edit or set the learner profile
+    Profile(ProfileCmd),
+    /// A graded reader on the topic, checked against the known words
+    Reader(String),
+    /// Read a paste until EOF, sent as a document after the question
+    Paste(String),
+    /// List the code blocks of the last answer (no index), or output one
+    Code {
+        index: Option<usize>,
+        output: CodeOutput,
+    },
+}
+
+/// Where `/code <n>` outputs the code block.
+#[derive(Debug)]
+pub enum CodeOutput {
+    Print,
+    /// `> file`
+    Write(String),
+    /// `>> file`
+    Append(String),
+    /// `| cmd`
+    Pipe(String),
+}
+
+/// What `/profile` does.
+#[derive(Debug)]
+pub enum ProfileCmd {
+    Show,
+    /// In $EDITOR (the TOML file)
+    Edit,
+    /// e.g., `/profile level HSK 3` (no value clears the field)
+    Set {
+        field: String,
+        value: String,
+    },
+}
+
+/// The registry entry of a slash command.
+#[derive(Debug)]
+pub struct CmdSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub arg: ArgSpec,
+    pub help: &'static str,
+    kind: CmdKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ArgSpec {
+    None,
+    Optional(ArgKind),
+    /// Zero or more
+    Many(ArgKind),
+    /// The rest of the line, parsed by the command (the usage args)
+    Line(&'static str),
+}
+
+/// What an argument is (for the usage and the completion).
+#[derive(Debug, Clone, Copy)]
+pub enum ArgKind {
+    /// A file bundle name (e.g., "knowledge")
+    Bundle,
+    /// A command name (e.g., "/rf")
+    Cmd,
+    /// A local file path
+    Path,
+    /// One of the values
+    Choice(&'static [&'static str]),
+    /// A Chinese word (no completion)
+    Word,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CmdKind {
+    Help,
+    Quit,
+    RefreshAll,
+    RefreshConversation,
+    RefreshInstructions,
+    RefreshFiles,
+    Attach,
+    Code,
+    Paste,
+    Edit,
+    Status,
+    Pinyin,
+    Save,
+    Review,
+    Level,
+    Quiz,
+    Profile,
+    Correct,
+    Dict,
+    Script,
+    Reader,
+}
+
+/// Invalid slash command input (never sent to the model).
+#[derive(Debug)]
+pub enum CmdError {
+    Unknown {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    TooManyArgs {
+        usage: String,
+    },
+    InvalidArgs {
+        usage: String,
+    },
+}
+
+// endregion:    -- Types
+
+// region:       -- Registry
+
+pub const CMD_SPECS: &[CmdSpec] = &[
+    CmdSpec {
+        name: "/help",
+        aliases: &["/h", "/?"],
+        arg: ArgSpec::Optional(ArgKind::Cmd),
+        help: "Show the commands, or the help of one command",
+        kind: CmdKind::Help,
+    },
+    CmdSpec {
+        name: "/q",
+        aliases: &["/quit", "/exit"],
+        arg: ArgSpec::None,
+        help: "Quit",
+        kind: CmdKind::Quit,
+    },
+    CmdSpec {
+        name: "/ra",
+        aliases: &["/r"],
+        arg: ArgSpec::None,
+        help: "Recreate the assistant, instructions, files and conversation",
+        kind: CmdKind::RefreshAll,
+    },
+    CmdSpec {
+        name: "/ri",
+        aliases: &[],
+        arg: ArgSpec::None,
+        help: "Upload the instructions and start a new conversation",
+        kind: CmdKind::RefreshInstructions,
+    },
+    CmdSpec {
+        name: "/rf",
+        aliases: &[],
+        arg: ArgSpec::Optional(ArgKind::Bundle),
+        help: "Re-upload the file bundles (or one) and start a new conversation",
+        kind: CmdKind::RefreshFiles,
+    },
+    CmdSpec {
+        name: "/rc",
+        aliases: &[],
+        arg: ArgSpec::None,
+        help: "Start a new conversation",
+        kind: CmdKind::RefreshConversation,
+    },
+    CmdSpec {
+        name: "/attach",
+        aliases: &["/a"],
+        arg: ArgSpec::Many(ArgKind::Path),
+        help: "Upload files for the next message (none lists the pending ones)",
+        kind: CmdKind::Attach,
+    },
+    CmdSpec {
+        name: "/code",
+        aliases: &[],
+        arg: ArgSpec::Line("[n] [> file | >> file | | cmd]"),
+        help: "List the code blocks of the last answer, or print/save/pipe one",
+        kind: CmdKind::Code,
+    },
+    CmdSpec {
+        name: "/paste",
+        aliases: &["/p"],
+        arg: ArgSpec::Line("[question]"),
+        help: "Paste a text until Ctrl-D, sent as a document after the question",
+        kind: CmdKind::Paste,
+    },
+    CmdSpec {
+        name: "/edit",
+        aliases: &["/e"],
+        arg: ArgSpec::None,
+        help: "Compose the message in $EDITOR (from the last one), empty cancels",
+        kind: CmdKind::Edit,
+    },
+    CmdSpec {
+        name: "/status",
+        aliases: &["/s"],
+        arg: ArgSpec::None,
+        help: "Show the assistant, instructions, files, thread and token usage",
+        kind: CmdKind::Status,
+    },
+    CmdSpec {
+        name: "/pinyin",
+        aliases: &["/py"],
+        arg: ArgSpec::Optional(ArgKind::Choice(PINYIN_CHOICES)),
+        help: "Annotate the Chinese with pinyin (ruby lines or inline), or set the tones",
+        kind: CmdKind::Pinyin,
+    },
+    CmdSpec {
+        name: "/script",
+        aliases: &[],
+        arg: ArgSpec::Optional(ArgKind::Choice(&["trad", "simp", "off"])),
+        help: "Show the responses in Traditional or Simplified characters (offline)",
+        kind: CmdKind::Script,
+    },
+    CmdSpec {
+        name: "/dict",
+        aliases: &["/d"],
+        arg: ArgSpec::Line("<word|pinyin|english>"),
+        help: "Look up the local CC-CEDICT (hanzi, pinyin with or without tones, English)",
+        kind: CmdKind::Dict,
+    },
+    CmdSpec {
+        name: "/save",
+        aliases: &[],
+        arg: ArgSpec::Many(ArgKind::Word),
+        help: "Save words to the vocabulary deck (none saves the new words of the last answer)",
+        kind: CmdKind::Save,
+    },
+    CmdSpec {
+        name: "/review",
+        aliases: &["/rv"],
+        arg: ArgSpec::None,
+        help: "Review the due words of the vocabulary deck (spaced repetition)",
+        kind: CmdKind::Review,
+    },
+    CmdSpec {
+        name: "/level",
+        aliases: &["/hsk"],
+        arg: ArgSpec::Line("[text]"),
+        help: "Show the HSK levels of the words of the text (or of the last answer)",
+        kind: CmdKind::Level,
+    },
+    CmdSpec {
+        name: "/quiz",
+        aliases: &[],
+        arg: ArgSpec::Line("[topic] [n]"),
+        help: "Quiz on the file bundles (n questions, default 5), graded by the laoshi",
+        kind: CmdKind::Quiz,
+    },
+    CmdSpec {
+        name: "/reader",
+        aliases: &[],
+        arg: ArgSpec::Line("<topic>"),
+        help: "A short story at your level (deck and HSK words), saved with a glossary",
+        kind: CmdKind::Reader,
+    },
+    CmdSpec {
+        name: "/profile",
+        aliases: &["/pf"],
+        arg: ArgSpec::Line("[edit | field [value]]"),
+        help: "Show the learner profile (sent with each message), edit it, or set a field",
+        kind: CmdKind::Profile,
+    },
+    CmdSpec {
+        name: "/correct",
+        aliases: &[],
+        arg: ArgSpec::Optional(ArgKind::Choice(&["on", "off"])),
+        help: "Correct the Chinese you write (diffed, errors in `laoshi grammar stats`)",
+        kind: CmdKind::Correct,
+    },
+];
+
+/// The `/quiz` questions when no `n`.
+const DEFAULT_QUIZ_QUESTIONS: usize = 5;
+
+const PINYIN_CHOICES: &[&str] = &["off", "ruby", "inline", "marks", "numbers"];
+
+/// Finds the command spec by name or alias (e.g., "/r" -> "/ra").
+pub fn find_spec(name: &str) -> Option<&'static CmdSpec> {
+    CMD_SPECS
+        .iter()
+        .find(|spec| spec.name == name || spec.aliases.contains(&name))
+}
+
+impl CmdSpec {
+    /// e.g., "/rf [bundle]"
+    pub fn usage(&self) -> String {
+        match self.arg {
+            ArgSpec::None => self.name.to_string(),
+            ArgSpec::Optional(kind) => format!("{} [{}]", self.name, kind.name()),
+            ArgSpec::Many(kind) => format!("{} [{}]...", self.name, kind.name()),
+            ArgSpec::Line(args) => format!("{} {args}", self.name),
+        }
+    }
+
+    fn names(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.name).chain(self.aliases.iter().copied())
+    }
+}
+
+impl ArgKind {
+    fn name(&self) -> String {
+        match self {
+            ArgKind::Bundle => "bundle".to_string(),
+            ArgKind::Cmd => "command".to_string(),
+            ArgKind::Path => "path".to_string(),
+            ArgKind::Choice(values) => values.join("|"),
+            ArgKind::Word => "word".to_string(),
+        }
+    }
+}
+
+// endregion:    -- Registry
+
+// region:       -- Parsing
+
+impl Cmd {
+    pub fn from_input(input: impl Into<String>) -> Result<Self, CmdError> {
+        // NOTE: Always need to shadow + .into() to ensure conversion
+        let input = input.into();
+
+        // -- Chat (`//` escapes a leading `/`, on a single line only)
+        // NOTE: A multi-line input (e.g., a pasted snippet starting with
+        // `// comment` or `/*`) is a command only if it starts with a known
+        // command name, and is otherwise sent as is.
+        let is_multi_line = input.contains('\n');
+        if !is_multi_line {
+            if let Some(msg) = input.strip_prefix("//") {
+                return Ok(Self::Chat(format!("/{msg}")));
+            }
+        }
+        let first_word = input.split_whitespace().next().unwrap_or_default();
+        let is_cmd = find_spec(first_word).is_some()
+            || (!is_multi_line && is_cmd_like(first_word));
+        if !is_cmd {
+            return Ok(Self::Chat(input));
+        }
+
+        // -- Slash command
+        let mut parts = input.split_whitespace();
+        let name = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+
+        let spec = find_spec(name).ok_or_else(|| CmdError::Unknown {
+            name: name.to_string(),
+            suggestion: suggest(name),
+        })?;
+
+        let args: Vec<String> = match (spec.arg, args.as_slice()) {
+            // Parsed by the command (below)
+            (ArgSpec::Line(_), _) => Vec::new(),
+            (ArgSpec::None, []) | (ArgSpec::Optional(_), [] | [_]) => {
+                args.iter().map(|arg| arg.to_string()).collect()
+            }
+            (ArgSpec::Many(_), args) => {
+                args.iter().map(|arg| arg.to_string()).collect()
+            }
+            _ => {
+                return Err(CmdError::TooManyArgs {
+                    usage: spec.usage(),
+                })
+            }
+        };
+
+        let arg = args.first().cloned();
+        let cmd = match spec.kind {
+            CmdKind::Help => Self::Help(arg),
+            CmdKind::Quit => Self::Quit,
+            CmdKind::RefreshAll => Self::RefreshAll,
+            CmdKind::RefreshConversation => Self::RefreshConversation,
+            CmdKind::RefreshInstructions => Self::RefreshInstructions,
+            CmdKind::RefreshFiles => Self::RefreshFiles(arg),
+            CmdKind::Attach => Self::Attach(args),
+            CmdKind::Code => parse_code_args(input[name.len()..].trim())
+                .ok_or_else(|| CmdError::InvalidArgs {
+                    usage: spec.usage(),
+                })?,
+            CmdKind::Paste => Self::Paste(input[name.len()..].trim().to_string()),
+            CmdKind::Edit => Self::Edit,
+            CmdKind::Status => Self::Status,
+            CmdKind::Pinyin => Self::Pinyin(match arg.as_deref() {
+                None => None,
+                Some(arg) => Some(parse_pinyin_arg(arg).ok_or_else(|| {
+                    CmdError::InvalidArgs {
+                        usage: spec.usage(),
+                    }
+                })?),
+            }),
+            CmdKind::Save => Self::Save(args),
+            CmdKind::Review => Self::Review,
+            CmdKind::Level => Self::Level(input[name.len()..].trim().to_string()),
+            CmdKind::Quiz => parse_quiz_args(input[name.len()..].trim())
+                .ok_or_else(|| CmdError::InvalidArgs {
+                    usage: spec.usage(),
+                })?,
+            CmdKind::Reader => match input[name.len()..].trim() {
+                "" => {
+                    return Err(CmdError::InvalidArgs {
+                        usage: spec.usage(),
+                    })
+                }
+                topic => Self::Reader(topic.to_string()),
+            },
+            CmdKind::Script => Self::Script(match arg.as_deref() {
+                None => None,
+                Some("off") => Some(None),
+                Some(arg) => {
+                    Some(Some(Script::from_name(arg).ok_or_else(|| {
+                        CmdError::InvalidArgs {
+                            usage: spec.usage(),
+                        }
+                    })?))
+                }
+            }),
+            CmdKind::Dict => match input[name.len()..].trim() {
+                "" => {
+                    return Err(CmdError::InvalidArgs {
+                        usage: spec.usage(),
+                    })
+                }
+                query => Self::Dict(query.to_string()),
+            },
+            CmdKind::Correct => Self::Correct(match arg.as_deref() {
+                None => None,
+                Some("on") => Some(true),
+                Some("off") => Some(false),
+                Some(_) => {
+                    return Err(CmdError::InvalidArgs {
+                        usage: spec.usage(),
+                    })
+                }
+            }),
+            CmdKind::Profile => parse_profile_args(input[name.len()..].trim())
+                .ok_or_else(|| CmdError::InvalidArgs {
+                    usage: spec.usage(),
+                })?,
+        };
+
+        Ok(cmd)
+    }
+}
+
+/// e.g., "", "2", "2 > src/foo.rs", "2 >> notes.md", "2 | wc -l"
+fn parse_code_args(line: &str) -> Option<Cmd> {
+    if line.is_empty() {
+        return Some(Cmd::Code {
+            index: None,
+            output: CodeOutput::Print,
+        });
+    }
+
+    let split_at = line.find(['>', '|']).unwrap_or(line.len());
+    let (index, output) = line.split_at(split_at);
+    let index: usize = index.trim().parse().ok().filter(|index| *index > 0)?;
+
+    let output = if let Some(file) = output.strip_prefix(">>") {
+        CodeOutput::Append(file.trim().to_string())
+    } else if let Some(file) = output.strip_prefix('>') {
+        CodeOutput::Write(file.trim().to_string())
+    } else if let Some(cmd) = output.strip_prefix('|') {
+        CodeOutput::Pipe(cmd.trim().to_string())
+    } else {
+        CodeOutput::Print
+    };
+    // No empty file or command
+    match &output {
+        CodeOutput::Write(arg) | CodeOutput::Append(arg) | CodeOutput::Pipe(arg)
+            if arg.is_empty() =>
+        {
+            None
+        }
+        _ => Some(Cmd::Code {
+            index: Some(index),
+            output,
+        }),
+    }
+}
+
+/// e.g., "", "10", "error handling", "error handling 3"
+fn parse_quiz_args(line: &str) -> Option<Cmd> {
+    let (topic, n) = line.rsplit_once(' ').unwrap_or(("", line));
+    let (topic, num_questions) = match n.parse::<usize>() {
+        Ok(n) => (topic, n),
+        Err(_) => (line, DEFAULT_QUIZ_QUESTIONS),
+    };
+    if !(1..=MAX_QUIZ_QUESTIONS).contains(&num_questions) {
+        return None;
+    }
+    let topic = topic.trim();
+    Some(Cmd::Quiz {
+        topic: (!topic.is_empty()).then(|| topic.to_string()),
+        num_questions,
+    })
+}
+
+/// e.g., "", "edit", "level HSK 3", "avoid_topics politics, sports", "goals"
+fn parse_profile_args(line: &str) -> Option<Cmd> {
+    let cmd = match line.split_once(' ').unwrap_or((line, "")) {
+        ("", _) => ProfileCmd::Show,
+        ("edit", "") => ProfileCmd::Edit,
+        (field, value) if PROFILE_FIELDS.contains(&field) => ProfileCmd::Set {
+            field: field.to_string(),
+            value: value.trim().to_string(),
+        },
+        _ => return None,
+    };
+    Some(Cmd::Profile(cmd))
+}
+
+fn parse_pinyin_arg(arg: &str) -> Option<PinyinSetting> {
+    let setting = match arg {
+        "off" => PinyinSetting::Display(PinyinDisplay::Off),
+        "ruby" => PinyinSetting::Display(PinyinDisplay::Ruby),
+        "inline" => PinyinSetting::Display(PinyinDisplay::Inline),
+        "marks" => PinyinSetting::Tones(ToneStyle::Marks),
+        "numbers" => PinyinSetting::Tones(ToneStyle::Numbers),
+        _ => return None,
+    };
+    Some(setting)
+}
+
+/// e.g., "/hepl" or "/" (reported if unknown), but not "/*" or "/tmp/foo".
+fn is_cmd_like(word: &str) -> bool {
+    word.strip_prefix('/').is_some_and(|name| {
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_?".contains(c))
+    })
+}
+
+/// The closest command name (or alias), if close enough to be a typo.
+fn suggest(name: &str) -> Option<&'static str> {
+    if name == "/" {
+        return Some("/help");
+    }
+    CMD_SPECS
+        .iter()
+        .flat_map(|spec| spec.names().map(move |n| (spec.name, n)))
+        .map(|(spec_name, n)| (spec_name, strsim::levenshtein(name, n)))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(spec_name, _)| spec_name)
+}
+
+// endregion:    -- Parsing
+
+// region:       -- Help
+
+/// The help of all the commands, or of the `name` command.
+pub fn help_text(name: Option<&str>) -> Result<String, CmdError> {
+    let specs: Vec<&CmdSpec> = match name {
+        Some(name) => {
+            // NOTE: Allow `/help rf` as well as `/help /rf`
+            let name = format!("/{}", name.trim_start_matches('/'));
+            let spec = find_spec(&name).ok_or_else(|| CmdError::Unknown {
+                suggestion: suggest(&name),
+                name,
+            })?;
+            vec![spec]
+        }
+        None => CMD_SPECS.iter().collect(),
+    };
+
+    let width = specs
+        .iter()
+        .map(|s| s.usage().len())
+        .max()
+        .unwrap_or_default();
+    let mut lines: Vec<String> = specs
+        .iter()
+        .map(|spec| {
+            let mut line = format!("{:<width$}  {}", spec.usage(), spec.help);
+            if !spec.aliases.is_empty() {
+                line.push_str(&format!(" (alias: {})", spec.aliases.join(", ")));
+            }
+            line
+        })
+        .collect();
+    if name.is_none() {
+        lines.push(String::new());
+        lines.push(
+            "Anything else is sent to the laoshi (// for a leading /).".into(),
+        );
+        lines.push("Press Tab to complete the commands and arguments.".into());
+        lines.push("Alt-Enter for a new line, Ctrl-R to search the history.".into());
+    }
+
+    Ok(lines.join("\n"))
+}
+
+// endregion:    -- Help
+
+// region:       -- Completion
+
+/// Tab completion of the command names and arguments (e.g., bundle names).
+pub struct CmdCompletion {
+    pub bundle_names: Vec<String>,
+}
+
+impl CmdCompletion {
+    /// The completion candidates of the `line` (up to the cursor), with the
+    /// start position of the word they complete.
+    pub fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        if !line.starts_with('/') || line.contains('\n') {
+            return (0, Vec::new());
+        }
+
+        match line.split_once(' ') {
+            // -- Command name (with a trailing space if it takes an argument)
+            None => {
+                let candidates = CMD_SPECS
+                    .iter()
+                    .filter(|spec| spec.name.starts_with(line))
+                    .map(|spec| match spec.arg {
+                        ArgSpec::None => spec.name.to_string(),
+                        ArgSpec::Optional(_)
+                        | ArgSpec::Many(_)
+                        | ArgSpec::Line(_) => {
+                            format!("{} ", spec.name)
+                        }
+                    })
+                    .collect();
+                (0, candidates)
+            }
+            // -- Command argument
+            Some((name, args)) => {
+                let (kind, arg, start) = match find_spec(name).map(|s| s.arg) {
+                    Some(ArgSpec::Optional(kind)) if !args.contains(' ') => {
+                        (kind, args, name.len() + 1)
+                    }
+                    // NOTE: Completes the last argument
+                    Some(ArgSpec::Many(kind)) => {
+                        let start =
+                            line.rfind(' ').map(|i| i + 1).unwrap_or_default();
+                        (kind, &line[start..], start)
+                    }
+                    _ => return (0, Vec::new()),
+                };
+                let candidates = match kind {
+                    ArgKind::Bundle => prefixed(&self.bundle_names, arg),
+                    ArgKind::Cmd => {
+                        let names: Vec<String> =
+                            CMD_SPECS.iter().map(|s| s.name.to_string()).collect();
+                        prefixed(&names, arg)
+                    }
+                    ArgKind::Path => path_candidates(arg),
+                    ArgKind::Choice(values) => {
+                        let values: Vec<String> =
+                            values.iter().map(|v| v.to_string()).collect();
+                        prefixed(&values, arg)
+                    }
+                    ArgKind::Word => Vec::new(),
+                };
+                (start, candidates)
+            }
+        }
+    }
+}
+
+fn prefixed(values: &[String], prefix: &str) -> Vec<String> {
+    values
+        .iter()
+        .filter(|value| value.starts_with(prefix))
+        .cloned()
+        .collect()
+}
+
+/// The paths starting with `prefix` (dirs with a trailing `/`).
+fn path_candidates(prefix: &str) -> Vec<String> {
+    let (dir, file_prefix) = match prefix.rfind('/') {
+        Some(idx) => (&prefix[..=idx], &prefix[idx + 1..]),
+        None => ("", prefix),
+    };
+    let Ok(entries) = std::fs::read_dir(if dir.is_empty() { "." } else { dir })
+    else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // NOTE: Hidden files only when asked for (e.g., `.laoshi/`)
+            if !name.starts_with(file_prefix)
+                || (name.starts_with('.') && !file_prefix.starts_with('.'))
+            {
+                return None;
+            }
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            Some(format!("{dir}{name}{}", if is_dir { "/" } else { "" }))
+        })
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+// endregion:    -- Completion
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for CmdError {
+    fn fmt(
+        &self,
+        fmt: &mut core::fmt::Formatter,
+    ) -> core::result::Result<(), core::fmt::Error> {
+        match self {
+            CmdError::Unknown {
+                name,
+                suggestion: Some(suggestion),
+            } => write!(
+                fmt,
+                "Unknown command '{name}'. Did you mean '{suggestion}'?"
+            ),
+            CmdError::Unknown { name, .. } => {
+                write!(
+                    fmt,
+                    "Unknown command '{name}'. Type /help for the commands."
+                )
+            }
+            CmdError::TooManyArgs { usage } => {
+                write!(fmt, "Too many arguments. Usage: {usage}")
+            }
+            CmdError::InvalidArgs { usage } => {
+                write!(fmt, "Invalid arguments. Usage: {usage}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CmdError {}
+// endregion: --- Error Boilerplate
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    #[test]
+    fn test_from_input_commands() -> Result<()> {
+        // -- Exec & Check
+        assert!(matches!(Cmd::from_input("/q")?, Cmd::Quit));
+        assert!(matches!(Cmd::from_input("/ra")?, Cmd::RefreshAll));
+        assert!(matches!(
+            Cmd::from_input("/rf knowledge")?,
+            Cmd::RefreshFiles(Some(bundle)) if bundle == "knowledge"
+        ));
+        assert!(matches!(
+            Cmd::from_input("/help rf")?,
+            Cmd::Help(Some(name)) if name == "rf"
+        ));
+        assert!(matches!(
+            Cmd::from_input("/rf a b"),
+            Err(CmdError::TooManyArgs { .. })
+        ));
+        // a known command on the first line of a multi-line input
+        assert!(matches!(Cmd::from_input("/q\n")?, Cmd::Quit));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_input_unknown_commands() -> Result<()> {
+        // -- Exec & Check
+        assert!(matches!(
+            Cmd::from_input("/hepl"),
+            Err(CmdError::Unknown {
+                suggestion: Some("/help"),
+                ..
+            })
+        ));
+        assert!(matches!(
+            Cmd::from_input("/"),
+            Err(CmdError::Unknown {
+                suggestion: Some("/help"),
+                ..
+            })
+        ));
+        assert!(matches!(
+            Cmd::from_input("/zzzzzz"),
+            Err(CmdError::Unknown {
+                suggestion: None,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_input_chat() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_pastes = [
+            "// comment\nfn main() {}",
+            "/* block */\nlet x = 1;",
+            "/// doc\nfn foo() {}",
+            "/hepl\nnot a command",
+        ];
+
+        // -- Exec & Check
+        assert!(
+            matches!(Cmd::from_input("hello")?, Cmd::Chat(msg) if msg == "hello")
+        );
+        // `//` escapes a leading `/` on a single line
+        assert!(matches!(Cmd::from_input("//ra")?, Cmd::Chat(msg) if msg == "/ra"));
+        assert!(matches!(
+            Cmd::from_input("/* not a command */")?,
+            Cmd::Chat(msg) if msg == "/* not a command */"
+        ));
+        assert!(matches!(
+            Cmd::from_input("/tmp/foo.rs does not build")?,
+            Cmd::Chat(_)
+        ));
+        // multi-line pastes are sent as is
+        for fx_paste in fx_pastes {
+            assert!(matches!(
+                Cmd::from_input(fx_paste)?,
+                Cmd::Chat(msg) if msg == fx_paste
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_code_args() -> Result<()> {
+        // -- Exec & Check
+        assert!(matches!(
+            parse_code_args(""),
+            Some(Cmd::Code {
+                index: None,
+                output: CodeOutput::Print
+            })
+        ));
+        assert!(matches!(
+            parse_code_args("2"),
+            Some(Cmd::Code {
+                index: Some(2),
+                output: CodeOutput::Print
+            })
+        ));
+        assert!(matches!(
+            parse_code_args("2 > src/foo.rs"),
+            Some(Cmd::Code { index: Some(2), output: CodeOutput::Write(file) })
+                if file == "src/foo.rs"
+        ));
+        assert!(matches!(
+            parse_code_args("1 >> notes.md"),
+            Some(Cmd::Code { index: Some(1), output: CodeOutput::Append(file) })
+                if file == "notes.md"
+        ));
+        assert!(matches!(
+            parse_code_args("3 | wc -l"),
+            Some(Cmd::Code { index: Some(3), output: CodeOutput::Pipe(cmd) })
+                if cmd == "wc -l"
+        ));
+        // no index 0, no empty file or command
+        assert!(parse_code_args("0").is_none());
+        assert!(parse_code_args("x").is_none());
+        assert!(parse_code_args("2 >").is_none());
+        assert!(parse_code_args("2 |  ").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_input_code() -> Result<()> {
+        // -- Exec & Check
+        assert!(matches!(
+            Cmd::from_input("/code 2 > src/foo.rs")?,
+            Cmd::Code { index: Some(2), output: CodeOutput::Write(file) }
+                if file == "src/foo.rs"
+        ));
+        assert!(matches!(
+            Cmd::from_input("/code x"),
+            Err(CmdError::InvalidArgs { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_quiz_args() -> Result<()> {
+        // -- Exec & Check
+        assert!(matches!(
+            parse_quiz_args(""),
+            Some(Cmd::Quiz {
+                topic: None,
+                num_questions: DEFAULT_QUIZ_QUESTIONS
+            })
+        ));
+        assert!(matches!(
+            parse_quiz_args("10"),
+            Some(Cmd::Quiz {
+                topic: None,
+                num_questions: 10
+            })
+        ));
+        assert!(matches!(
+            parse_quiz_args("error handling"),
+            Some(Cmd::Quiz { topic: Some(topic), num_questions: DEFAULT_QUIZ_QUESTIONS })
+                if topic == "error handling"
+        ));
+        assert!(matches!(
+            parse_quiz_args("error handling 3"),
+            Some(Cmd::Quiz { topic: Some(topic), num_questions: 3 })
+                if topic == "error handling"
+        ));
+        // 1 to MAX_QUIZ_QUESTIONS questions
+        assert!(parse_quiz_args("0").is_none());
+        assert!(parse_quiz_args(&format!("{}", MAX_QUIZ_QUESTIONS + 1)).is_none());
+        assert!(matches!(
+            Cmd::from_input("/quiz traits 0"),
+            Err(CmdError::InvalidArgs { .. })
+        ));
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
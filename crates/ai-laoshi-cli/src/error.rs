@@ -1,19 +1,22 @@
 use derive_more::From;
-use std::io;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug, From)]
 pub enum Error {
+    NoQuestion,
+    ReviewNeedsTerminal,
     // -- App Libs
     #[from]
     AILaoshi(ai_laoshi_core::Error),
     //
-    // // -- Externals
-    // #[from]
-    // IO(io::Error),
+    // -- Externals
+    #[from]
+    IO(std::io::Error),
     #[from]
     Dialoguer(dialoguer::Error),
+    #[from]
+    Readline(rustyline::error::ReadlineError),
 }
 
 // region:    --- Error Boilerplate
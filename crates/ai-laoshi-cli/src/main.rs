@@ -1,18 +1,42 @@
 // region:       -- Modules
+mod args;
+mod cmd;
 mod error;
+mod tui;
 mod utils;
 
 pub use self::error::{Error, Result};
-use crate::utils::cli::{icon_check, icon_res, prompt, txt_res};
-
-use ai_laoshi_core::{
-    ais::{
-        assistant::{self, CreateConfig},
-        new_openai_client,
-    },
-    Laoshi,
+use crate::args::{Args, ConfigCmd, GrammarCmd, QuizCmd, SubCmd, VocabCmd};
+use crate::cmd::{help_text, Cmd, CmdCompletion, ProfileCmd};
+use crate::utils::cli::{
+    confirm, edit_message, icon_check, icon_err, icon_uploaded, print_res,
+    read_piped_stdin, read_stdin,
 };
-use textwrap::wrap;
+use crate::utils::code::exec_code;
+use crate::utils::dict::{print_dict, DEFAULT_DICT_LIMIT};
+use crate::utils::editor::LineEditor;
+use crate::utils::grammar::{correct_text, print_grammar_stats};
+use crate::utils::level::{print_level, rewrite_request};
+use crate::utils::plan::print_plan;
+use crate::utils::profile::{edit_profile, print_profile};
+use crate::utils::quiz::{print_quiz_stats, run_quiz};
+use crate::utils::reader::run_reader;
+use crate::utils::status::print_status;
+use crate::utils::theme::{init_theme, theme};
+use crate::utils::tutor::{
+    apply_profile, correct_mode, init_tutor, load_cedict, pinyin_status,
+    script_status, set_correct_mode, set_pinyin, set_script, tutor_err_msg,
+};
+use crate::utils::vocab::{
+    auto_save_words, export_vocab, import_vocab, review, save_words,
+};
+
+use ai_laoshi_core::utils::document::{frame_document, MAX_DOCUMENT_CHARS};
+use ai_laoshi_core::{Conversation, Laoshi, Plan, Status};
+use clap::Parser;
+use console::style;
+use std::io::IsTerminal;
+use std::path::Path;
 
 // endregion:    -- Modules
 
@@ -20,75 +44,98 @@ use textwrap::wrap;
 async fn main() {
     // NOTE: Preference is to keep main() small, and then
     // use other helpers to run the loop, etc.
-    println!();
+    let args = Args::parse();
+    // NOTE: A config error is reported by the command itself
+    init_theme(Laoshi::theme_config_from_dir(DEFAULT_DIR).unwrap_or_default());
+    init_tutor(
+        DEFAULT_DIR,
+        Laoshi::tutor_config_from_dir(DEFAULT_DIR).unwrap_or_default(),
+    );
 
-    match start().await {
-        Ok(_) => println!("\nBye!\n"),
-        Err(e) => println!("\nError: {}\n", e),
-    }
-}
-
-const DEFAULT_DIR: &str = "laoshi";
-
-// region:       -- Types
-
-/// Input Command from user
-// NOTE: When a user says something, we'll map it to this Enum.
-// Our app will have a loop that constantly does this.
-#[derive(Debug)]
-enum Cmd {
-    // TODO: Could add a Help cmd to display options
-    Quit,
-    Chat(String),
-    RefreshAll,
-    RefreshConversation,
-    RefreshInstructions,
-    RefreshFiles,
-}
+    match args.cmd {
+        // -- No subcommand, start the chat loop
+        None => {
+            println!();
 
-// Next, need to parse the Enum. We could impl From str
-// or can impl helper methods.
-// NOTE: TIP! We allow input to be a general type, but we
-// want to own it (String), instead of a ref &str, since
-// we have Cmd::Chat(String) that's owned. This could
-// return Result<Self> if we wanted.
-impl Cmd {
-    fn from_input(input: impl Into<String>) -> Self {
-        // NOTE: Always need to shadow + .into() to ensure conversion
-        let input = input.into();
-
-        if input == "/q" {
-            Self::Quit
-        } else if input == "/r" || input == "/ra" {
-            Self::RefreshAll
-        } else if input == "/ri" {
-            Self::RefreshInstructions
-        } else if input == "/rf" {
-            Self::RefreshFiles
-        } else if input == "/rc" {
-            Self::RefreshConversation
-        } else {
-            Self::Chat(input)
+            match start(args.raw).await {
+                Ok(_) => println!("\nBye!\n"),
+                Err(e) => println!("\n{} Error: {}\n", icon_err(), e),
+            }
+        }
+        // -- One-shot subcommand (keep stdout clean for redirects)
+        Some(sub_cmd) => {
+            if let Err(e) = exec_sub_cmd(sub_cmd, args.raw).await {
+                eprintln!("{} Error: {}", icon_err(), tutor_err_msg(&e));
+                std::process::exit(1);
+            }
         }
     }
 }
 
-// endregion:    -- Types
+const DEFAULT_DIR: &str = "laoshi";
 
-async fn start() -> Result<()> {
+async fn start(raw: bool) -> Result<()> {
     println!("->> hello world");
     // -- Init our Agent/Laoshi
     let mut laoshi = Laoshi::init_from_dir(DEFAULT_DIR, false).await?;
 
     // -- Init the Conversation
     let mut conversation = laoshi.load_or_create_conversation(false).await?;
+    println!("{} Laoshi {} ready", icon_check(), laoshi.name());
+    if let Err(e) = load_cedict() {
+        println!("{} {}", icon_err(), tutor_err_msg(&e));
+    }
+    // NOTE: The level and script of the learner profile, if any (see /script)
+    if let Err(e) = apply_profile(laoshi.profile()) {
+        println!("{} {}", icon_err(), tutor_err_msg(&e));
+    }
+
+    // -- Line editor, with the Tab completion of the commands (and bundle
+    // names for /rf) and the history in laoshi/.laoshi/history
+    let completion = CmdCompletion {
+        bundle_names: laoshi
+            .bundle_names()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    };
+    let mut editor =
+        LineEditor::new(laoshi.data_dir()?.join("history"), completion)?;
+
+    // The last message (for /edit) and answer (for /code)
+    let mut last_msg: Option<String> = None;
+    let mut last_res: Option<String> = None;
 
     // -- Start our app loop
     loop {
         println!();
 
-        let input = prompt("Ask away!")?;
-        let cmd = Cmd::from_input(input);
+        // NOTE: None is Ctrl-D, an empty input (e.g., Ctrl-C) asks again
+        // -- Prompt (with the pending attachments, if any)
+        let attachments: Vec<&str> = conversation
+            .pending_attachments()
+            .iter()
+            .map(|a| a.file_name.as_str())
+            .collect();
+        let prompt_text = match attachments.is_empty() {
+            true => theme().prompt.clone(),
+            false => format!("{} [+ {}]", theme().prompt, attachments.join(", ")),
+        };
+
+        let Some(input) = editor.read(&prompt_text)? else {
+            break;
+        };
+        if input.trim().is_empty() {
+            continue;
+        }
+        // NOTE: Invalid commands are reported, never sent to the model
+        let cmd = match Cmd::from_input(input) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                println!("{} {e}", icon_err());
+                continue;
+            }
+        };
 
         // Q: Match on Cmd and have agent take over?
         // Q: How to quit? How to refresh?
@@ -96,10 +143,151 @@ async fn start() -> Result<()> {
         // and we convert to a Cmd variant, which we then parse/match here.
         match cmd {
             Cmd::Chat(msg) => {
-                let res = laoshi.chat(&conversation, &msg).await?;
-                let res = wrap(&res, 80).join("\n");
-                println!("{} {}", utils::cli::icon_res(), utils::cli::txt_res(res));
+                correct(&laoshi, &msg).await;
+                let res = reply(&laoshi, &mut conversation, &msg, raw).await?;
+                last_msg = Some(msg);
+                last_res = Some(res);
+            }
+            Cmd::Edit => {
+                let Some(msg) = edit_message(last_msg.as_deref())? else {
+                    println!("Empty message, cancelled.");
+                    continue;
+                };
+                println!("{msg}");
+                correct(&laoshi, &msg).await;
+                let res = reply(&laoshi, &mut conversation, &msg, raw).await?;
+                last_msg = Some(msg);
+                last_res = Some(res);
+            }
+            Cmd::Status => match laoshi.status(&conversation).await {
+                Ok(status) => print_status(&status),
+                Err(e) => println!("{} {e}", icon_err()),
+            },
+            Cmd::Pinyin(None) => println!("{}", pinyin_status()),
+            Cmd::Pinyin(Some(setting)) => match set_pinyin(setting) {
+                Ok(_) => println!("{} {}", icon_check(), pinyin_status()),
+                Err(e) => println!("{} {}", icon_err(), tutor_err_msg(&e)),
+            },
+            Cmd::Save(words) => {
+                let dir = Path::new(DEFAULT_DIR);
+                if let Err(e) = save_words(dir, &words, last_res.as_deref()) {
+                    println!("{} {}", icon_err(), tutor_err_msg(&e));
+                }
+            }
+            Cmd::Quiz {
+                topic,
+                num_questions,
+            } => {
+                let dir = Path::new(DEFAULT_DIR);
+                // NOTE: e.g., an invalid quiz JSON should not end the session
+                if let Err(e) = run_quiz(
+                    dir,
+                    &laoshi,
+                    &mut editor,
+                    topic.as_deref(),
+                    num_questions,
+                )
+                .await
+                {
+                    println!("{} {e}", icon_err());
+                }
+            }
+            Cmd::Script(None) => println!("{}", script_status()),
+            Cmd::Script(Some(script)) => match set_script(script) {
+                Ok(_) => println!("{} {}", icon_check(), script_status()),
+                Err(e) => println!("{} {}", icon_err(), tutor_err_msg(&e)),
+            },
+            Cmd::Dict(query) => {
+                if let Err(e) = print_dict(&query, DEFAULT_DICT_LIMIT) {
+                    println!("{} {}", icon_err(), tutor_err_msg(&e));
+                }
+            }
+            Cmd::Correct(correct) => {
+                if let Some(correct) = correct {
+                    set_correct_mode(correct);
+                }
+                let mode = if correct_mode() { "on" } else { "off" };
+                println!("Grammar correction: {mode}");
             }
+            Cmd::Profile(ProfileCmd::Show) => print_profile(laoshi.profile()),
+            Cmd::Profile(ProfileCmd::Edit) => {
+                let Some(profile) = edit_profile(laoshi.profile())? else {
+                    println!("Profile unchanged.");
+                    continue;
+                };
+                laoshi.set_profile(profile)?;
+                println!(
+                    "{} Profile saved, used from the next message",
+                    icon_check()
+                );
+                if let Err(e) = apply_profile(laoshi.profile()) {
+                    println!("{} {}", icon_err(), tutor_err_msg(&e));
+                }
+            }
+            Cmd::Profile(ProfileCmd::Set { field, value }) => {
+                let mut profile = laoshi.profile().clone();
+                // NOTE: e.g., an invalid script should not end the session
+                if let Err(e) = profile.set(&field, &value) {
+                    println!("{} {e}", icon_err());
+                    continue;
+                }
+                laoshi.set_profile(profile)?;
+                print_profile(laoshi.profile());
+                if let Err(e) = apply_profile(laoshi.profile()) {
+                    println!("{} {}", icon_err(), tutor_err_msg(&e));
+                }
+            }
+            Cmd::Reader(topic) => {
+                let dir = Path::new(DEFAULT_DIR);
+                // NOTE: e.g., a missing CC-CEDICT should not end the session
+                if let Err(e) = run_reader(dir, &laoshi, &topic, raw).await {
+                    println!("{} {}", icon_err(), tutor_err_msg(&e));
+                }
+            }
+            Cmd::Level(text) => {
+                let text = match (text.is_empty(), last_res.as_deref()) {
+                    (false, _) => text.as_str(),
+                    (true, Some(last_res)) => last_res,
+                    (true, None) => {
+                        println!("No answer yet. Usage: /level [text]");
+                        continue;
+                    }
+                };
+                if let Err(e) = print_level(text) {
+                    println!("{} {}", icon_err(), tutor_err_msg(&e));
+                }
+            }
+            Cmd::Review => {
+                if let Err(e) = review(Path::new(DEFAULT_DIR)) {
+                    println!("{} {e}", icon_err());
+                }
+            }
+            Cmd::Paste(question) => {
+                println!("Paste the text, then Ctrl-D (on an empty line) to send:");
+                let content = read_stdin()?;
+                if content.trim().is_empty() {
+                    println!("Nothing pasted.");
+                    continue;
+                }
+                let framed =
+                    frame_document(&question, "paste", &content, MAX_DOCUMENT_CHARS);
+                if framed.truncated_chars > 0 {
+                    println!("Note: {} chars truncated", framed.truncated_chars);
+                }
+                let res =
+                    reply(&laoshi, &mut conversation, &framed.message, raw).await?;
+                last_res = Some(res);
+            }
+            Cmd::Code { index, output } => {
+                // NOTE: e.g., a write error should not end the session
+                if let Err(e) = exec_code(last_res.as_deref(), index, output) {
+                    println!("{} {e}", icon_err());
+                }
+            }
+            Cmd::Help(name) => match help_text(name.as_deref()) {
+                Ok(help) => println!("{help}"),
+                Err(e) => println!("{} {e}", icon_err()),
+            },
             Cmd::Quit => break,
             Cmd::RefreshAll => {
                 // NOTE:The init helper handles deleting/recreating assistant, instructions, files, etc.
@@ -114,11 +302,203 @@ async fn start() -> Result<()> {
                 // NOTE: ! Need to recreate the conversation!
                 conversation = laoshi.load_or_create_conversation(true).await?;
             }
-            Cmd::RefreshFiles => {
+            Cmd::RefreshFiles(None) => {
                 laoshi.upload_files(true).await?;
                 conversation = laoshi.load_or_create_conversation(true).await?;
             }
+            Cmd::RefreshFiles(Some(bundle_name)) => {
+                // NOTE: A typo in the bundle name should not end the session
+                if let Err(e) =
+                    laoshi.upload_files_of_bundle(&bundle_name, true).await
+                {
+                    println!("{} {e}", icon_err());
+                    continue;
+                }
+                conversation = laoshi.load_or_create_conversation(true).await?;
+            }
+            Cmd::Attach(paths) if paths.is_empty() => {
+                match conversation.pending_attachments() {
+                    [] => println!("No pending attachments."),
+                    attachments => {
+                        for attachment in attachments {
+                            println!("+ {}", attachment.file_name);
+                        }
+                    }
+                }
+            }
+            Cmd::Attach(paths) => {
+                for path in paths {
+                    // NOTE: A wrong path should not end the session
+                    match laoshi.attach_file(&mut conversation, &path).await {
+                        Ok(attachment) => println!(
+                            "{} {} attached to the next message",
+                            icon_uploaded(),
+                            attachment.file_name
+                        ),
+                        Err(e) => println!("{} {e}", icon_err()),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Corrects the Chinese of the learner `msg` if the grammar mode is on
+/// (errors are reported, the message is still sent).
+async fn correct(laoshi: &Laoshi, msg: &str) {
+    if !correct_mode() {
+        return;
+    }
+    if let Err(e) = correct_text(Path::new(DEFAULT_DIR), laoshi, msg).await {
+        println!("{} {e}", icon_err());
+    }
+}
+
+/// Sends the `msg`, prints the response (rewritten if above the learner HSK
+/// level, see the [tutor] hsk_rewrite) and saves its new words (auto_save).
+async fn reply(
+    laoshi: &Laoshi,
+    conversation: &mut Conversation,
+    msg: &str,
+    raw: bool,
+) -> Result<String> {
+    let mut res = laoshi.chat(conversation, msg).await?;
+    print_res(&res, raw);
+    if let Some(request) = rewrite_request(&res) {
+        println!(
+            "\n{}",
+            style("Above your HSK level, asking for a rewrite...").dim()
+        );
+        res = laoshi.chat(conversation, &request).await?;
+        print_res(&res, raw);
+    }
+    auto_save_words(Path::new(DEFAULT_DIR), &res);
+    Ok(res)
+}
+
+async fn exec_sub_cmd(sub_cmd: SubCmd, raw: bool) -> Result<()> {
+    match sub_cmd {
+        SubCmd::Instructions { print: true } => {
+            // NOTE: Composing does not need the assistant (no OpenAI calls)
+            match Laoshi::compose_instructions_from_dir(DEFAULT_DIR)? {
+                Some(ix_content) => print!("{ix_content}"),
+                None => eprintln!("No instructions files found in '{DEFAULT_DIR}'"),
+            }
+        }
+        SubCmd::Instructions { print: false } => {
+            // NOTE: Only the instructions (the bundles are `laoshi apply`)
+            let laoshi = Laoshi::load_from_dir(DEFAULT_DIR, false).await?;
+            match laoshi.upload_instructions().await? {
+                true => {
+                    println!(
+                        "{} {} instructions uploaded",
+                        icon_check(),
+                        laoshi.name()
+                    )
+                }
+                false => eprintln!("No instructions files found in '{DEFAULT_DIR}'"),
+            }
+        }
+        SubCmd::Plan => {
+            let plan = Plan::from_dir(DEFAULT_DIR).await?;
+            print_plan(&plan);
+        }
+        SubCmd::Apply { yes } => {
+            let plan = Plan::from_dir(DEFAULT_DIR).await?;
+            print_plan(&plan);
+            if plan.is_empty() {
+                return Ok(());
+            }
+
+            println!();
+            if !yes && !confirm("Apply these changes?")? {
+                println!("Apply cancelled.");
+                return Ok(());
+            }
+            let laoshi = plan.apply().await?;
+            println!("{} Laoshi {} applied", icon_check(), laoshi.name());
+        }
+        SubCmd::Ask {
+            question,
+            upload,
+            max_chars,
+        } => {
+            let question = question.join(" ");
+            let stdin = read_piped_stdin()?.filter(|s| !s.trim().is_empty());
+            if question.trim().is_empty() && stdin.is_none() {
+                return Err(Error::NoQuestion);
+            }
+
+            let laoshi = Laoshi::init_from_dir(DEFAULT_DIR, false).await?;
+            let mut conversation = laoshi.load_or_create_conversation(false).await?;
+
+            let msg = match stdin {
+                None => question,
+                // -- Too large, uploaded as a file (if asked)
+                Some(content) if upload && content.chars().count() > max_chars => {
+                    let file = laoshi.data_dir()?.join("stdin.txt");
+                    std::fs::write(&file, &content)?;
+                    laoshi.attach_file(&mut conversation, &file).await?;
+                    format!("{question}\n\n(The document is the attached stdin.txt file.)")
+                }
+                // -- Framed in the message (truncated if too large)
+                Some(content) => {
+                    let framed =
+                        frame_document(&question, "stdin", &content, max_chars);
+                    if framed.truncated_chars > 0 {
+                        eprintln!(
+                            "Note: {} chars of stdin truncated (see --max-chars, --upload)",
+                            framed.truncated_chars
+                        );
+                    }
+                    framed.message
+                }
+            };
+
+            let res = laoshi.chat(&mut conversation, &msg).await?;
+            // NOTE: As is when redirected (e.g., `> review.md`)
+            if raw || !std::io::stdout().is_terminal() {
+                println!("{res}");
+            } else {
+                // NOTE: A missing CC-CEDICT only leaves the script as is
+                let _ = apply_profile(laoshi.profile());
+                print_res(&res, false);
+            }
+        }
+        SubCmd::Dict { query, limit } => print_dict(&query.join(" "), limit)?,
+        SubCmd::Status => print_status(&Status::from_dir(DEFAULT_DIR).await?),
+        SubCmd::Tui => tui::run(DEFAULT_DIR).await?,
+        SubCmd::Config {
+            cmd: ConfigCmd::Show,
+        } => {
+            let layers = Laoshi::config_layers_from_dir(DEFAULT_DIR)?;
+            let entries = layers.entries();
+            // NOTE: Align the `# source` comments (output stays valid TOML-ish)
+            let width = entries
+                .iter()
+                .map(|e| e.key.len() + e.value.len() + 3)
+                .filter(|w| *w <= 60) // long values (e.g., bundles) not aligned
+                .max()
+                .unwrap_or_default();
+            for entry in entries {
+                let line = format!("{} = {}", entry.key, entry.value);
+                println!("{line:<width$}  # {}", entry.source);
+            }
         }
+        SubCmd::Quiz {
+            cmd: QuizCmd::Stats,
+        } => print_quiz_stats(Path::new(DEFAULT_DIR))?,
+        SubCmd::Grammar {
+            cmd: GrammarCmd::Stats,
+        } => print_grammar_stats(Path::new(DEFAULT_DIR))?,
+        SubCmd::Vocab {
+            cmd: VocabCmd::Export { anki, tsv },
+        } => export_vocab(Path::new(DEFAULT_DIR), anki.as_deref(), tsv.as_deref())?,
+        SubCmd::Vocab {
+            cmd: VocabCmd::Import { file },
+        } => import_vocab(Path::new(DEFAULT_DIR), &file)?,
     }
 
     Ok(())
@@ -0,0 +1,105 @@
+// NOTE: The TUI state: the transcript, the input box, the scroll position,
+// the side panel info, and the background task (chat or new conversation)
+// being waited on.
+use crate::tui::ui::tui_color;
+use crate::utils::theme::theme;
+use ai_laoshi_core::{BundleState, Conversation};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Borders};
+use tui_textarea::TextArea;
+
+/// One entry of the transcript.
+pub struct Entry {
+    pub role: Role,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Role {
+    User,
+    Laoshi,
+    /// Command output, status
+    Info,
+    Error,
+}
+
+/// The laoshi info of the side panel.
+pub struct Side {
+    pub name: String,
+    pub model: String,
+    pub bundles: Vec<BundleState>,
+    /// The thread ids of this session (the last one is the active one)
+    pub threads: Vec<String>,
+}
+
+pub struct App {
+    pub side: Side,
+    pub entries: Vec<Entry>,
+    pub input: TextArea<'static>,
+    /// Lines scrolled up from the bottom of the transcript
+    pub scroll_up: u16,
+    /// The transcript lines above the viewport, the max `scroll_up` (set on draw)
+    pub max_scroll: u16,
+    /// `None` while a task (chat, new conversation) has it
+    pub conversation: Option<Conversation>,
+    /// What the running task is doing (for the status bar)
+    pub task_status: &'static str,
+    /// Animation tick while waiting on a task
+    pub tick: usize,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(side: Side, conversation: Conversation) -> Self {
+        Self {
+            side,
+            entries: Vec::new(),
+            input: new_input(),
+            scroll_up: 0,
+            max_scroll: 0,
+            conversation: Some(conversation),
+            task_status: "",
+            tick: 0,
+            quit: false,
+        }
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.conversation.is_none()
+    }
+
+    pub fn push(&mut self, role: Role, text: impl Into<String>) {
+        self.entries.push(Entry {
+            role,
+            text: text.into(),
+        });
+        // NOTE: Back to the bottom to see the new entry
+        self.scroll_up = 0;
+    }
+
+    /// Takes the input text (and clears the input box).
+    pub fn take_input(&mut self) -> String {
+        let input = std::mem::replace(&mut self.input, new_input());
+        input.into_lines().join("\n")
+    }
+
+    pub fn scroll(&mut self, lines: i32) {
+        // NOTE: Clamped, so scrolling back down is immediate after a PgUp past the top
+        let scroll_up =
+            (self.scroll_up as i32 + lines).clamp(0, self.max_scroll as i32);
+        self.scroll_up = scroll_up as u16;
+    }
+}
+
+fn new_input() -> TextArea<'static> {
+    let mut input = TextArea::default();
+    input.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(tui_color(theme().accent)))
+            .title(format!(" {} ", theme().prompt)),
+    );
+    input.set_cursor_line_style(Style::default());
+    input.set_placeholder_text("Enter to send, Alt-Enter for a new line, /help");
+    input
+}
@@ -0,0 +1,194 @@
+// NOTE: `laoshi tui`, a full-screen chat (ratatui) on top of the same core
+// APIs as the REPL (Laoshi, Conversation) and the same command registry.
+// The OpenAI calls run in tokio tasks, so the screen stays responsive while
+// the laoshi is thinking.
+
+// region:       -- Modules
+
+mod app;
+mod ui;
+
+use crate::cmd::{help_text, Cmd};
+use crate::tui::app::{App, Role, Side};
+use crate::utils::tutor::{
+    apply_profile, load_cedict, pinyin_status, script_status, set_pinyin,
+    set_script, tutor_err_msg,
+};
+use crate::Result;
+use ai_laoshi_core::{Conversation, Laoshi};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+// endregion:    -- Modules
+
+const TICK_MS: u64 = 100;
+
+/// The result of a background task, with the conversation back.
+enum TaskDone {
+    Chat {
+        conversation: Conversation,
+        res: ai_laoshi_core::Result<String>,
+    },
+    NewConversation(ai_laoshi_core::Result<Conversation>, Option<Conversation>),
+}
+
+pub async fn run(dir: &str) -> Result<()> {
+    // NOTE: Init before the full screen (the init prints its status)
+    let mut laoshi = Laoshi::init_from_dir(dir, false).await?;
+    let conversation = laoshi.load_or_create_conversation(false).await?;
+    laoshi.set_quiet(true);
+    if let Err(e) = load_cedict() {
+        println!("{}", tutor_err_msg(&e));
+    }
+    if let Err(e) = apply_profile(laoshi.profile()) {
+        println!("{}", tutor_err_msg(&e));
+    }
+
+    let side = Side {
+        name: laoshi.name().to_string(),
+        model: laoshi.model().to_string(),
+        bundles: laoshi.bundle_states()?,
+        threads: vec![conversation.to_string()],
+    };
+    let mut app = App::new(side, conversation);
+    let laoshi = Arc::new(laoshi);
+
+    let mut terminal = ratatui::init();
+    let res = run_loop(&mut terminal, &mut app, laoshi).await;
+    ratatui::restore();
+
+    res
+}
+
+async fn run_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    laoshi: Arc<Laoshi>,
+) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<TaskDone>();
+
+    while !app.quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        // -- Background task results
+        while let Ok(done) = rx.try_recv() {
+            on_task_done(app, done);
+        }
+
+        // -- Key events (or tick)
+        if !event::poll(Duration::from_millis(TICK_MS))? {
+            app.tick += 1;
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                app.quit = true
+            }
+            (KeyCode::PageUp, _) => app.scroll(10),
+            (KeyCode::PageDown, _) => app.scroll(-10),
+            // Alt-Enter, a new line in the input
+            (KeyCode::Enter, KeyModifiers::ALT) => app.input.insert_newline(),
+            (KeyCode::Enter, _) => {
+                if !app.is_waiting() {
+                    let input = app.take_input();
+                    on_input(app, &laoshi, &tx, input);
+                }
+            }
+            _ => {
+                app.input.input(key);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn on_input(
+    app: &mut App,
+    laoshi: &Arc<Laoshi>,
+    tx: &mpsc::UnboundedSender<TaskDone>,
+    input: String,
+) {
+    if input.trim().is_empty() {
+        return;
+    }
+
+    let cmd = match Cmd::from_input(input) {
+        Ok(cmd) => cmd,
+        Err(e) => return app.push(Role::Error, e.to_string()),
+    };
+
+    match cmd {
+        Cmd::Chat(msg) => {
+            let Some(mut conversation) = app.conversation.take() else {
+                return;
+            };
+            app.push(Role::User, msg.clone());
+            app.task_status = "Laoshi is thinking...";
+            let (laoshi, tx) = (laoshi.clone(), tx.clone());
+            tokio::spawn(async move {
+                let res = laoshi.chat(&mut conversation, &msg).await;
+                let _ = tx.send(TaskDone::Chat { conversation, res });
+            });
+        }
+        Cmd::RefreshConversation => {
+            let old_conversation = app.conversation.take();
+            app.task_status = "Starting a new conversation...";
+            let (laoshi, tx) = (laoshi.clone(), tx.clone());
+            tokio::spawn(async move {
+                let res = laoshi.load_or_create_conversation(true).await;
+                let _ = tx.send(TaskDone::NewConversation(res, old_conversation));
+            });
+        }
+        Cmd::Help(name) => match help_text(name.as_deref()) {
+            Ok(help) => app.push(Role::Info, help),
+            Err(e) => app.push(Role::Error, e.to_string()),
+        },
+        Cmd::Pinyin(None) => app.push(Role::Info, pinyin_status()),
+        Cmd::Pinyin(Some(setting)) => match set_pinyin(setting) {
+            Ok(_) => app.push(Role::Info, pinyin_status()),
+            Err(e) => app.push(Role::Error, tutor_err_msg(&e)),
+        },
+        Cmd::Script(None) => app.push(Role::Info, script_status()),
+        Cmd::Script(Some(script)) => match set_script(script) {
+            Ok(_) => app.push(Role::Info, script_status()),
+            Err(e) => app.push(Role::Error, tutor_err_msg(&e)),
+        },
+        Cmd::Quit => app.quit = true,
+        // NOTE: The other commands print to the terminal, or need it (e.g., $EDITOR)
+        _ => app.push(Role::Info, "Not available in the TUI (use `laoshi`)."),
+    }
+}
+
+fn on_task_done(app: &mut App, done: TaskDone) {
+    match done {
+        TaskDone::Chat { conversation, res } => {
+            app.conversation = Some(conversation);
+            match res {
+                Ok(res) => app.push(Role::Laoshi, res),
+                Err(e) => app.push(Role::Error, e.to_string()),
+            }
+        }
+        TaskDone::NewConversation(Ok(conversation), _) => {
+            app.side.threads.push(conversation.to_string());
+            app.conversation = Some(conversation);
+            app.entries.clear();
+            app.push(Role::Info, "New conversation.");
+        }
+        // NOTE: Keep the previous conversation if a new one can't be created
+        TaskDone::NewConversation(Err(e), old_conversation) => {
+            app.conversation = old_conversation;
+            app.push(Role::Error, e.to_string());
+        }
+    }
+}
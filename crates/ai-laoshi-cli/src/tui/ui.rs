@@ -0,0 +1,246 @@
+// NOTE: Draws the TUI: the transcript (laoshi answers rendered with the same
+// markdown renderer as the REPL), the input box, the side panel (laoshi,
+// model, bundles, conversations) and the status bar, in the [theme] colors
+// (none in plain mode, e.g., NO_COLOR).
+use crate::tui::app::{App, Role};
+use crate::utils::markdown;
+use crate::utils::theme::theme;
+use crate::utils::tutor::{annotate_text, convert_script};
+use ansi_to_tui::IntoText;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const ASCII_SPINNER: &[&str] = &["|", "/", "-", "\\"];
+const HINTS: &[&str] = &[
+    "Enter send",
+    "Alt-Enter new line",
+    "PgUp/PgDn scroll",
+    "/help",
+    "Esc quit",
+];
+const SIDE_WIDTH: u16 = 30;
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main_area, status_area] =
+        Layout::vertical([Constraint::Min(6), Constraint::Length(1)])
+            .areas(frame.area());
+    // NOTE: No side panel on narrow terminals
+    let (chat_area, side_area) = if main_area.width >= SIDE_WIDTH * 3 {
+        let [chat_area, side_area] = Layout::horizontal([
+            Constraint::Min(40),
+            Constraint::Length(SIDE_WIDTH),
+        ])
+        .areas(main_area);
+        (chat_area, Some(side_area))
+    } else {
+        (main_area, None)
+    };
+
+    let input_height = (app.input.lines().len() as u16 + 2).clamp(3, 10);
+    let [transcript_area, input_area] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(input_height)])
+            .areas(chat_area);
+
+    // -- Transcript (scrolled to the bottom, minus `scroll_up`)
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(dim_color()))
+        .title(format!(" Laoshi {} ", app.side.name));
+    let inner_width = transcript_area.width.saturating_sub(2) as usize;
+    let inner_height = transcript_area.height.saturating_sub(2);
+    let text = transcript_text(app, inner_width);
+    let max_scroll = (text.lines.len() as u16).saturating_sub(inner_height);
+    // NOTE: Re-clamped here too, the viewport changes on resize
+    app.max_scroll = max_scroll;
+    app.scroll_up = app.scroll_up.min(max_scroll);
+    let scroll = max_scroll - app.scroll_up;
+    frame.render_widget(
+        Paragraph::new(text).block(block).scroll((scroll, 0)),
+        transcript_area,
+    );
+
+    // -- Input
+    frame.render_widget(&app.input, input_area);
+
+    // -- Side panel
+    if let Some(side_area) = side_area {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(dim_color()));
+        frame.render_widget(Paragraph::new(side_text(app)).block(block), side_area);
+    }
+
+    // -- Status bar (task status, or ready), upload status and key hints
+    let status = if app.is_waiting() {
+        let frames = if theme().ascii {
+            ASCII_SPINNER
+        } else {
+            SPINNER
+        };
+        let spinner = frames[app.tick % frames.len()];
+        Span::styled(
+            format!(" {spinner} {}", app.task_status),
+            Style::default().fg(tui_color(theme().warning)),
+        )
+    } else {
+        Span::styled(
+            format!(" {} ready", theme().glyph("●", "*")),
+            Style::default().fg(tui_color(theme().success)),
+        )
+    };
+    let uploads = upload_status(app);
+    let hints = Span::styled(
+        format!(
+            "  {}",
+            HINTS.join(&format!(" {} ", theme().glyph("·", "|")))
+        ),
+        Style::default().fg(dim_color()),
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![status, uploads, hints])),
+        status_area,
+    );
+}
+
+fn upload_status(app: &App) -> Span<'static> {
+    let bundles = &app.side.bundles;
+    if bundles.is_empty() {
+        return Span::styled(
+            format!("  {} no bundles", theme().glyph("·", "|")),
+            Style::default().fg(dim_color()),
+        );
+    }
+    let num_uploaded = bundles.iter().filter(|b| b.uploaded).count();
+    let color = if num_uploaded == bundles.len() {
+        theme().success
+    } else {
+        theme().error
+    };
+    Span::styled(
+        format!(
+            "  {} bundles {num_uploaded}/{} uploaded",
+            theme().glyph("·", "|"),
+            bundles.len()
+        ),
+        Style::default().fg(tui_color(color)),
+    )
+}
+
+fn side_text(app: &App) -> Text<'static> {
+    let title = Style::default()
+        .fg(tui_color(theme().accent))
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(dim_color());
+    let side = &app.side;
+
+    let mut lines = vec![
+        Line::from(Span::styled("Laoshi", title)),
+        Line::from(format!(" {}", side.name)),
+        Line::from(Span::styled(format!(" {}", side.model), dim)),
+        Line::default(),
+        Line::from(Span::styled("Bundles", title)),
+    ];
+    if side.bundles.is_empty() {
+        lines.push(Line::from(Span::styled(" (none)", dim)));
+    }
+    for bundle in side.bundles.iter() {
+        let (mark, color) = if bundle.uploaded {
+            (theme().glyph("✔", "+"), tui_color(theme().success))
+        } else {
+            (theme().glyph("✗", "x"), tui_color(theme().error))
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {mark} "), Style::default().fg(color)),
+            Span::raw(bundle.bundle_name.clone()),
+            Span::styled(format!(" ({} files)", bundle.num_files), dim),
+        ]));
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled("Conversations", title)));
+    let active = side.threads.len().saturating_sub(1);
+    for (idx, thread_id) in side.threads.iter().enumerate().rev() {
+        let line = if idx == active {
+            Line::from(format!(" {} {thread_id}", theme().glyph("▸", ">")))
+        } else {
+            Line::from(Span::styled(format!("   {thread_id}"), dim))
+        };
+        lines.push(line);
+    }
+
+    Text::from(lines)
+}
+
+fn transcript_text(app: &App, width: usize) -> Text<'static> {
+    let mut lines: Vec<Line> = Vec::new();
+    for entry in app.entries.iter() {
+        let (label, color) = match entry.role {
+            Role::User => ("you", tui_color(theme().success)),
+            Role::Laoshi => ("laoshi", tui_color(theme().accent)),
+            Role::Info => ("info", dim_color()),
+            Role::Error => ("error", tui_color(theme().error)),
+        };
+        lines.push(Line::from(Span::styled(
+            format!("{label} {}", theme().glyph("›", ">")),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )));
+
+        match entry.role {
+            // NOTE: The renderer output is ANSI, converted to ratatui spans
+            // (the script converted for display, the entry keeps the original)
+            Role::Laoshi => {
+                let text = convert_script(&entry.text);
+                let rendered = markdown::render(&text, width, Some(annotate_text));
+                match rendered.into_text() {
+                    Ok(text) => lines.extend(text.lines),
+                    Err(_) => lines.extend(plain_lines(&entry.text, width)),
+                }
+            }
+            _ => lines.extend(plain_lines(&entry.text, width)),
+        }
+        lines.push(Line::default());
+    }
+    Text::from(lines)
+}
+
+fn plain_lines(text: &str, width: usize) -> Vec<Line<'static>> {
+    text.lines()
+        .flat_map(|line| textwrap::wrap(line, width.max(10)))
+        .map(|line| Line::from(line.into_owned()))
+        .collect()
+}
+
+// region:       -- Support
+
+/// The ratatui color of the theme `color` (`Color::Reset` in plain mode).
+pub(super) fn tui_color(color: console::Color) -> Color {
+    if theme().plain {
+        return Color::Reset;
+    }
+    match color {
+        console::Color::Black => Color::Black,
+        console::Color::Red => Color::Red,
+        console::Color::Green => Color::Green,
+        console::Color::Yellow => Color::Yellow,
+        console::Color::Blue => Color::Blue,
+        console::Color::Magenta => Color::Magenta,
+        console::Color::Cyan => Color::Cyan,
+        console::Color::White => Color::White,
+        console::Color::Color256(idx) => Color::Indexed(idx),
+    }
+}
+
+/// The color of the borders and secondary text (`Color::Reset` in plain mode).
+fn dim_color() -> Color {
+    if theme().plain {
+        Color::Reset
+    } else {
+        Color::DarkGray
+    }
+}
+
+// endregion:    -- Support
@@ -1,49 +1,123 @@
+use crate::utils::markdown;
+use crate::utils::theme::theme;
+use crate::utils::tutor::{annotate_text, convert_script};
 use crate::Result;
 use console::{style, Style, StyledObject};
-use dialoguer::{theme::ColorfulTheme, Input};
+use dialoguer::{theme::ColorfulTheme, Confirm, Editor};
+use std::io::{IsTerminal, Read};
 
 // region:       -- Prompts
 
-pub fn prompt(text: &str) -> Result<String> {
+/// Yes/No prompt (defaults to No)
+pub fn confirm(text: &str) -> Result<bool> {
+    let theme = prompt_theme();
+
+    let res = Confirm::with_theme(&theme)
+        .with_prompt(text)
+        .default(false)
+        .interact()?;
+
+    Ok(res)
+}
+
+fn prompt_theme() -> ColorfulTheme {
     // NOTE: There are several themes to consider so check the crate
-    let theme = ColorfulTheme {
-        prompt_style: Style::new().for_stderr().color256(45),
-        prompt_prefix: style("?".to_string()).color256(45).for_stderr(),
-        ..ColorfulTheme::default()
+    let accent = theme().accent;
+    let default = ColorfulTheme::default();
+    let success_prefix = match theme().ascii {
+        true => style("[ok]".to_string()).for_stderr().fg(theme().success),
+        false => default.success_prefix,
     };
+    ColorfulTheme {
+        prompt_style: Style::new().for_stderr().fg(accent),
+        prompt_prefix: style("?".to_string()).fg(accent).for_stderr(),
+        success_prefix,
+        ..default
+    }
+}
 
-    let input = Input::with_theme(&theme);
-    let res = input.with_prompt(text).interact_text()?;
+/// The first line of the `/edit` file (removed before sending).
+const EDIT_HINT: &str =
+    "<!-- Write your message below. Save and quit to send, an empty file cancels. -->";
 
-    Ok(res)
+/// Opens $VISUAL/$EDITOR on a markdown file pre-filled with `initial`.
+/// Returns `None` if the file is not saved or empty.
+pub fn edit_message(initial: Option<&str>) -> Result<Option<String>> {
+    let content = format!("{EDIT_HINT}\n\n{}\n", initial.unwrap_or_default());
+    let Some(edited) = edit_text(&content, ".md")? else {
+        return Ok(None);
+    };
+
+    let message = edited.replace(EDIT_HINT, "").trim().to_string();
+    Ok((!message.is_empty()).then_some(message))
+}
+
+/// Opens $VISUAL/$EDITOR on a file with the `extension` (e.g., ".toml")
+/// pre-filled with `content`. Returns `None` if the file is not saved.
+pub fn edit_text(content: &str, extension: &str) -> Result<Option<String>> {
+    // NOTE: Empty VISUAL/EDITOR are skipped (dialoguer would panic on them)
+    let editor_cmd = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|cmd| !cmd.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let edited = Editor::new()
+        .executable(editor_cmd)
+        .extension(extension)
+        .edit(content)?;
+    Ok(edited)
 }
 
 // endregion:    -- Prompts
 
+// region:       -- Stdin
+
+/// The piped stdin content, or `None` if stdin is a terminal.
+pub fn read_piped_stdin() -> Result<Option<String>> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Ok(None);
+    }
+    read_stdin().map(Some)
+}
+
+/// Reads stdin until EOF (Ctrl-D in a terminal).
+pub fn read_stdin() -> Result<String> {
+    let mut content = Vec::new();
+    std::io::stdin().read_to_end(&mut content)?;
+    // NOTE: Lossy, since logs can have some invalid UTF-8
+    Ok(String::from_utf8_lossy(&content).to_string())
+}
+
+// endregion:    -- Stdin
+
 // region:       -- Icons (unicode symbols on LHS of Prompts)
 
 pub fn icon_res() -> StyledObject<&'static str> {
-    style("➤").color256(45)
+    style(theme().icons.res.as_str()).fg(theme().accent)
 }
 
 pub fn icon_check() -> StyledObject<&'static str> {
-    style("✔").green()
+    style(theme().icons.check.as_str()).fg(theme().success)
 }
 
+#[allow(dead_code)] // Reserved for the upcoming ais events
 pub fn icon_uploading() -> StyledObject<&'static str> {
-    style("↥").yellow()
+    style(theme().icons.uploading.as_str()).fg(theme().warning)
 }
 
 pub fn icon_uploaded() -> StyledObject<&'static str> {
-    style("↥").green()
+    style(theme().icons.uploaded.as_str()).fg(theme().success)
 }
 
+#[allow(dead_code)] // Reserved for the upcoming ais events
 pub fn icon_deleted_ok() -> StyledObject<&'static str> {
-    style("⌫").green()
+    style(theme().icons.deleted.as_str()).fg(theme().success)
 }
 
 pub fn icon_err() -> StyledObject<&'static str> {
-    style("✗").red()
+    style(theme().icons.err.as_str()).fg(theme().error)
 }
 
 // endregion:    -- Icons (lhs of Prompts)
@@ -54,4 +128,31 @@ pub fn txt_res(text: String) -> StyledObject<String> {
     style(text).bright()
 }
 
+/// Prints a laoshi response, rendered as markdown unless `raw` (with the
+/// pinyin of the tutor display, if on).
+pub fn print_res(res: &str, raw: bool) {
+    // NOTE: Display only, the caller keeps the original `res`
+    let res = &convert_script(res);
+    if raw {
+        println!("{} {}", icon_res(), txt_res(annotate_text(res)));
+        return;
+    }
+
+    // NOTE: Indented under the icon
+    let indent = " ".repeat(console::measure_text_width(&theme().icons.res) + 1);
+    let width = markdown::term_width().saturating_sub(indent.len());
+    // NOTE: Only the prose is annotated, never the code blocks
+    let rendered = markdown::render(res, width, Some(annotate_text));
+    let lines: Vec<String> = rendered
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| match (idx, line.is_empty()) {
+            (0, _) => format!("{} {line}", icon_res()),
+            (_, true) => String::new(),
+            (_, false) => format!("{indent}{line}"),
+        })
+        .collect();
+    println!("{}", lines.join("\n"));
+}
+
 // endregion:    -- Text Output
@@ -0,0 +1,107 @@
+// NOTE: The `/code` command: lists the fenced code blocks of the last
+// answer, or prints, writes (`>`), appends (`>>`) or pipes (`|`) one of them.
+use crate::cmd::CodeOutput;
+use crate::utils::cli::{confirm, icon_check, icon_err};
+use crate::Result;
+use ai_laoshi_core::utils::code_blocks::{extract_code_blocks, CodeBlock};
+use console::style;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub fn exec_code(
+    answer: Option<&str>,
+    index: Option<usize>,
+    output: CodeOutput,
+) -> Result<()> {
+    let blocks = answer.map(extract_code_blocks).unwrap_or_default();
+    if blocks.is_empty() {
+        println!("No code blocks in the last answer.");
+        return Ok(());
+    }
+
+    // -- No index, list them
+    let Some(index) = index else {
+        print_list(&blocks);
+        return Ok(());
+    };
+    let Some(block) = blocks.get(index - 1) else {
+        println!(
+            "{} No code block {index} (1 to {})",
+            icon_err(),
+            blocks.len()
+        );
+        return Ok(());
+    };
+
+    match output {
+        CodeOutput::Print => print!("{}", block.code),
+        CodeOutput::Write(file) => {
+            let path = Path::new(&file);
+            if path.exists() && !confirm(&format!("Overwrite '{file}'?"))? {
+                println!("Not saved.");
+                return Ok(());
+            }
+            ensure_parent_dir(path)?;
+            fs::write(path, &block.code)?;
+            println!("{} Code block {index} saved to '{file}'", icon_check());
+        }
+        CodeOutput::Append(file) => {
+            let path = Path::new(&file);
+            ensure_parent_dir(path)?;
+            let mut out = OpenOptions::new().create(true).append(true).open(path)?;
+            out.write_all(block.code.as_bytes())?;
+            println!("{} Code block {index} appended to '{file}'", icon_check());
+        }
+        CodeOutput::Pipe(cmd) => pipe(&block.code, &cmd)?,
+    }
+
+    Ok(())
+}
+
+// region:       -- Support
+
+fn print_list(blocks: &[CodeBlock]) {
+    for (idx, block) in blocks.iter().enumerate() {
+        let lang = block.lang.as_deref().unwrap_or("text");
+        let lines = block.code.lines().count();
+        let first_line = block.code.lines().find(|l| !l.trim().is_empty());
+        println!(
+            "{:>2}. {:<10} {:>3} lines  {}",
+            idx + 1,
+            lang,
+            lines,
+            style(first_line.unwrap_or_default().trim()).dim()
+        );
+    }
+}
+
+/// Pipes the `code` to the shell `cmd` (its output goes to the terminal).
+fn pipe(code: &str, cmd: &str) -> Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(code.as_bytes())?;
+        // NOTE: stdin dropped here, so the command gets EOF
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        println!("{} '{cmd}' exited with {status}", icon_err());
+    }
+
+    Ok(())
+}
+
+fn ensure_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+// endregion:    -- Support
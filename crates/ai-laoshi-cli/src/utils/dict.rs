@@ -0,0 +1,59 @@
+// NOTE: The `/dict` and `laoshi dict` display of the local CC-CEDICT lookup
+// (see `Cedict::search`), no API call. e.g.,
+// 学 (學)  xué
+//   to learn; to study
+use crate::utils::theme::theme;
+use crate::utils::tutor::{cedict, tone_style};
+use crate::Result;
+use ai_laoshi_core::chinese::{format_pinyin, DictMatch};
+use console::style;
+
+/// The `/dict` results shown when no limit.
+pub const DEFAULT_DICT_LIMIT: usize = 10;
+
+/// Prints the first `limit` entries matching the `query` (hanzi, pinyin or
+/// English).
+pub fn print_dict(query: &str, limit: usize) -> Result<()> {
+    let cedict = cedict()?;
+    let hits = cedict.search(query);
+    if hits.is_empty() {
+        println!("No entry for '{query}'.");
+        return Ok(());
+    }
+
+    // NOTE: The match kind is shown when a query matches several ways
+    // (e.g., "ma" as pinyin and English)
+    let several_kinds = hits.iter().any(|hit| hit.matched != hits[0].matched);
+    let mut matched: Option<DictMatch> = None;
+    for hit in hits.iter().take(limit) {
+        if several_kinds && matched != Some(hit.matched) {
+            matched = Some(hit.matched);
+            let title = match hit.matched {
+                DictMatch::Hanzi => "Hanzi",
+                DictMatch::Pinyin => "Pinyin",
+                DictMatch::English => "English",
+            };
+            println!("{}", style(format!("-- {title}")).dim());
+        }
+
+        let entry = hit.entry;
+        let traditional = match entry.traditional != entry.simplified {
+            true => format!(" ({})", entry.traditional),
+            false => String::new(),
+        };
+        println!(
+            "{}{traditional}  {}",
+            style(&entry.simplified).bold(),
+            style(format_pinyin(&entry.pinyin, tone_style())).fg(theme().accent)
+        );
+        println!("  {}", entry.definitions.join("; "));
+    }
+    if hits.len() > limit {
+        println!(
+            "{}",
+            style(format!("... {} more entries", hits.len() - limit)).dim()
+        );
+    }
+
+    Ok(())
+}
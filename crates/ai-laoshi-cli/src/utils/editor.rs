@@ -0,0 +1,133 @@
+// NOTE: The REPL line editor (rustyline), with the input history persisted
+// in the laoshi data dir (laoshi/.laoshi/history), multi-line input (pasted
+// text is kept whole thanks to bracketed paste, Alt-Enter inserts a new line),
+// Ctrl-R reverse search and Tab completion of the slash commands.
+// The prompt keeps the dialoguer ColorfulTheme look (see utils::cli).
+use crate::cmd::CmdCompletion;
+use crate::utils::theme::theme;
+use crate::Result;
+use console::style;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{
+    Cmd as EditCmd, CompletionType, Config, Context, Editor, Helper, KeyCode,
+    KeyEvent, Modifiers,
+};
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+const HISTORY_MAX_SIZE: usize = 1000;
+
+pub struct LineEditor {
+    editor: Editor<LaoshiHelper, FileHistory>,
+    history_file: PathBuf,
+}
+
+impl LineEditor {
+    /// New editor, loading the history from `history_file` (if any).
+    pub fn new(history_file: PathBuf, completion: CmdCompletion) -> Result<Self> {
+        let config = Config::builder()
+            .max_history_size(HISTORY_MAX_SIZE)?
+            .history_ignore_dups(true)?
+            .history_ignore_space(true)
+            .completion_type(CompletionType::List)
+            .bracketed_paste(true)
+            .build();
+
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(LaoshiHelper { completion }));
+        // Alt-Enter inserts a new line (Enter sends)
+        editor.bind_sequence(
+            KeyEvent(KeyCode::Enter, Modifiers::ALT),
+            EditCmd::Newline,
+        );
+
+        // NOTE: No history file yet on first run, which is fine.
+        if history_file.exists() {
+            editor.load_history(&history_file)?;
+        }
+
+        Ok(Self {
+            editor,
+            history_file,
+        })
+    }
+
+    /// Reads one (possibly multi-line) input.
+    /// Returns `None` on Ctrl-D, and an empty input on Ctrl-C.
+    pub fn read(&mut self, text: &str) -> Result<Option<String>> {
+        let prompt = format!("? {text} {} ", theme().glyph("›", ">"));
+
+        let input = match self.editor.readline(&prompt) {
+            Ok(input) => input,
+            Err(ReadlineError::Interrupted) => return Ok(Some(String::new())),
+            Err(ReadlineError::Eof) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // NOTE: Appended on each input, so the history survives a crash.
+        if !input.trim().is_empty() && self.editor.add_history_entry(&input)? {
+            self.editor.append_history(&self.history_file)?;
+        }
+
+        Ok(Some(input))
+    }
+}
+
+// region:       -- Helper
+
+struct LaoshiHelper {
+    completion: CmdCompletion,
+}
+
+impl Helper for LaoshiHelper {}
+
+impl Completer for LaoshiHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completion.candidates(&line[..pos]))
+    }
+}
+
+impl Highlighter for LaoshiHelper {
+    // NOTE: Same look as the dialoguer ColorfulTheme of utils::cli::prompt_theme()
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(
+        &'s self,
+        prompt: &'p str,
+        _default: bool,
+    ) -> Cow<'b, str> {
+        let Some(text) = prompt
+            .strip_prefix("? ")
+            .and_then(|p| p.strip_suffix(" "))
+            .and_then(|p| p.strip_suffix(theme().glyph("›", ">")))
+            .and_then(|p| p.strip_suffix(' '))
+        else {
+            return Cow::Borrowed(prompt);
+        };
+        let accent = theme().accent;
+        Cow::Owned(format!(
+            "{} {} {} ",
+            style("?").fg(accent),
+            style(text).fg(accent),
+            style(theme().glyph("›", ">")).black().bright(),
+        ))
+    }
+}
+
+impl Hinter for LaoshiHelper {
+    type Hint = String;
+}
+
+impl Validator for LaoshiHelper {}
+
+// endregion:    -- Helper
@@ -0,0 +1,111 @@
+// NOTE: The grammar correction display: the learner text diffed with its
+// correction (removed chars struck in red, added ones underlined in green,
+// `[-x-]{+y+}` without colors), the errors by category, and the
+// `laoshi grammar stats` display of the recurring mistakes.
+use crate::utils::cli::{icon_check, icon_err};
+use crate::utils::theme::theme;
+use crate::Result;
+use ai_laoshi_core::chinese::{char_diff, Diff};
+use ai_laoshi_core::{Correction, GrammarLog, Laoshi};
+use console::{colors_enabled, style};
+use std::path::Path;
+
+/// Corrects the Chinese of the learner `text` (if any), prints the
+/// correction and records its errors in the laoshi `dir`.
+pub async fn correct_text(dir: &Path, laoshi: &Laoshi, text: &str) -> Result<()> {
+    let Some(correction) = laoshi.correct(text).await? else {
+        return Ok(());
+    };
+    print_correction(text, &correction);
+    GrammarLog::load_from_dir(dir)?.record(text, &correction)?;
+    Ok(())
+}
+
+pub fn print_correction(text: &str, correction: &Correction) {
+    if correction.is_correct(text) {
+        println!("{} No mistakes.", icon_check());
+        return;
+    }
+
+    println!("{} {}", icon_err(), diff_line(text, &correction.corrected));
+    for error in &correction.errors {
+        println!(
+            "  {} {} {} {}{}",
+            style(format!("[{}]", error.category.name())).fg(theme().accent),
+            error.wrong,
+            theme().glyph("→", "->"),
+            error.right,
+            match error.explanation.is_empty() {
+                true => String::new(),
+                false => format!(": {}", error.explanation),
+            }
+        );
+    }
+    if !correction.explanation.is_empty() {
+        println!("  {}", style(&correction.explanation).dim());
+    }
+}
+
+/// e.g.,
+/// category        errors  last
+/// measure word         4  2026-10-18
+///
+/// Recurring: 个 -> 本 (measure word) x3
+pub fn print_grammar_stats(dir: &Path) -> Result<()> {
+    let log = GrammarLog::load_from_dir(dir)?;
+    let stats = log.stats();
+    if stats.is_empty() {
+        println!("No mistakes recorded yet. Turn on /correct in `laoshi`.");
+        return Ok(());
+    }
+
+    println!("{}", style("category        errors  last").bold());
+    for s in stats {
+        println!(
+            "{:<14}  {:>6}  {}",
+            s.category.name(),
+            s.count,
+            s.last_date.format("%Y-%m-%d")
+        );
+    }
+
+    let recurring = log.recurring();
+    if !recurring.is_empty() {
+        println!("\n{}", style("Recurring mistakes").bold());
+        for r in recurring {
+            println!(
+                "  {} {} {} ({}) x{}",
+                r.wrong,
+                theme().glyph("→", "->"),
+                r.right,
+                r.category.name(),
+                r.count
+            );
+        }
+    }
+    println!("\n{} corrected messages with mistakes", log.entries().len());
+
+    Ok(())
+}
+
+// region:       -- Support
+
+/// The `text` with the changes of its `corrected` version.
+fn diff_line(text: &str, corrected: &str) -> String {
+    char_diff(text, corrected)
+        .into_iter()
+        .map(|diff| match (diff, colors_enabled()) {
+            (Diff::Same(text), _) => text,
+            (Diff::Removed(text), true) => {
+                style(text).fg(theme().error).strikethrough().to_string()
+            }
+            (Diff::Added(text), true) => {
+                style(text).fg(theme().success).underlined().to_string()
+            }
+            (Diff::Removed(text), false) => format!("[-{text}-]"),
+            (Diff::Added(text), false) => format!("{{+{text}+}}"),
+        })
+        .collect()
+}
+
+// endregion:    -- Support
@@ -0,0 +1,108 @@
+// NOTE: The `/level` HSK analysis display (the share of the words at each
+// level, and the words above the learner level), and the rewrite check of
+// the responses (the [tutor] hsk_rewrite).
+use crate::utils::cli::icon_err;
+use crate::utils::theme::theme;
+use crate::utils::tutor::{cedict, hsk_level, hsk_lists, tutor_err_msg};
+use crate::Result;
+use ai_laoshi_core::chinese::{analyze, is_above, Token};
+use console::style;
+
+const BAR_WIDTH: usize = 20;
+
+/// Prints the HSK analysis of the `text`.
+pub fn print_level(text: &str) -> Result<()> {
+    let (cedict, lists) = (cedict()?, hsk_lists()?);
+    let analysis = analyze(text, &cedict, &lists);
+    let words = analysis.words();
+    if words.is_empty() {
+        println!("No Chinese words in the text.");
+        return Ok(());
+    }
+    let version = analysis.version;
+
+    // -- The share of each level
+    println!("{} words (HSK {} lists)", words.len(), version.name());
+    let shares = analysis.shares();
+    let rows = version
+        .levels()
+        .iter()
+        .map(|level| (version.level_name(*level), shares.get(&Some(*level))))
+        .chain(std::iter::once(("unlisted".to_string(), shares.get(&None))));
+    for (name, share) in rows {
+        let share = share.copied().unwrap_or_default();
+        let bar = theme()
+            .glyph("█", "#")
+            .repeat((share * BAR_WIDTH as f32).round() as usize);
+        println!(
+            "  {name:<9} {:>4.0}%  {}",
+            share * 100.0,
+            style(bar).fg(theme().accent)
+        );
+    }
+
+    // -- The words above the learner level (highlighted in the text)
+    let (Some(level), _) = hsk_level() else {
+        println!(
+            "\nSet your level (e.g., /profile level HSK 3) to mark the words \
+             above it."
+        );
+        return Ok(());
+    };
+    let above: Vec<String> = analysis
+        .above(level)
+        .iter()
+        .filter_map(|w| {
+            Some(format!("{} ({})", w.word, version.level_name(w.level?)))
+        })
+        .collect();
+    println!();
+    // NOTE: The unlisted words (e.g., names) are not above the level
+    let unlisted: Vec<&str> = analysis.unlisted().iter().map(|w| w.word).collect();
+    if !unlisted.is_empty() {
+        println!("Unlisted: {}", unlisted.join(", "));
+    }
+    if above.is_empty() {
+        println!(
+            "All the words are at your level ({}).",
+            version.level_name(level)
+        );
+        return Ok(());
+    }
+    println!("Above {}: {}", version.level_name(level), above.join(", "));
+    let marked: String = analysis
+        .tokens
+        .iter()
+        .map(|(token, word_level)| match token {
+            Token::Word(word) if is_above(*word_level, level) => {
+                style(word).fg(theme().warning).underlined().to_string()
+            }
+            Token::Word(text) | Token::Text(text) => text.to_string(),
+        })
+        .collect();
+    println!("\n{marked}");
+
+    Ok(())
+}
+
+/// The message asking to rewrite the `res` if the [tutor] hsk_rewrite is on
+/// and the `res` goes above the learner level (errors are reported).
+pub fn rewrite_request(res: &str) -> Option<String> {
+    let (Some(level), true) = hsk_level() else {
+        return None;
+    };
+    let analyzed = cedict().and_then(|cedict| {
+        let lists = hsk_lists()?;
+        let analysis = analyze(res, &cedict, &lists);
+        Ok(analysis
+            .goes_above(level)
+            .then(|| analysis.rewrite_request(level)))
+    });
+    match analyzed {
+        Ok(request) => request,
+        Err(e) => {
+            println!("{} {}", icon_err(), tutor_err_msg(&e));
+            None
+        }
+    }
+}
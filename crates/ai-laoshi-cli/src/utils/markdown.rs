@@ -0,0 +1,466 @@
+// NOTE: Renders the laoshi (markdown) responses for the terminal: headings,
+// lists, emphasis, quotes, tables, and fenced code blocks with syntax
+// highlighting (syntect). Prose is wrapped to the given width, code blocks
+// and tables are never re-wrapped (so code can be copied as is), and only the
+// prose is annotated (e.g., with the pinyin of the tutor display).
+use crate::utils::theme::theme;
+use console::{measure_text_width, style, Style};
+use pulldown_cmark::{
+    CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
+};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+const CODE_THEME: &str = "base16-ocean.dark";
+
+/// Renders the `markdown` for a terminal of `width` columns, with each prose
+/// line (and table row) passed to `annotate`, if any (never the code blocks).
+pub fn render(
+    markdown: &str,
+    width: usize,
+    annotate: Option<fn(&str) -> String>,
+) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let mut renderer = Renderer::new(width, annotate);
+    for event in Parser::new_ext(markdown, options) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+/// The terminal width (80 if not a terminal).
+pub fn term_width() -> usize {
+    console::Term::stdout()
+        .size_checked()
+        .map(|(_, cols)| cols as usize)
+        .unwrap_or(80)
+}
+
+// region:       -- Renderer
+
+#[derive(Default)]
+struct Renderer {
+    width: usize,
+    /// Applied to the prose lines (not the code)
+    annotate: Option<fn(&str) -> String>,
+    lines: Vec<String>,
+    /// The current inline text (styled), flushed as wrapped lines
+    inline: String,
+    // -- Inline styles
+    strong: usize,
+    emphasis: usize,
+    strikethrough: usize,
+    link_urls: Vec<String>,
+    // -- Blocks
+    heading: Option<HeadingLevel>,
+    quote_depth: usize,
+    /// The next number of each (nested) list, `None` for bullets
+    lists: Vec<Option<u64>>,
+    /// The bullet (or number) of the item not printed yet
+    item_marker: Option<String>,
+    code_block: Option<CodeBlock>,
+    table: Option<Table>,
+}
+
+struct CodeBlock {
+    lang: String,
+    code: String,
+}
+
+#[derive(Default)]
+struct Table {
+    rows: Vec<Vec<String>>,
+    head_rows: usize,
+    cell: String,
+}
+
+impl Renderer {
+    fn new(width: usize, annotate: Option<fn(&str) -> String>) -> Self {
+        Self {
+            width: width.max(20),
+            annotate,
+            ..Default::default()
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.flush_inline();
+        self.lines.join("\n")
+    }
+
+    fn event(&mut self, event: Event) {
+        // -- Code blocks and tables collect their raw content
+        if let Some(code_block) = self.code_block.as_mut() {
+            match event {
+                Event::Text(text) => code_block.code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => self.end_code_block(),
+                _ => (),
+            }
+            return;
+        }
+        if let Some(table) = self.table.as_mut() {
+            match event {
+                Event::Text(text) | Event::Code(text) => table.cell.push_str(&text),
+                Event::End(TagEnd::TableCell) => {
+                    let cell = std::mem::take(&mut table.cell);
+                    if let Some(row) = table.rows.last_mut() {
+                        row.push(cell);
+                    }
+                }
+                Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => {
+                    table.rows.push(Vec::new())
+                }
+                Event::End(TagEnd::TableHead) => table.head_rows = table.rows.len(),
+                Event::End(TagEnd::Table) => self.end_table(),
+                _ => (),
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                let text = self.inline_style().apply_to(text.as_ref()).to_string();
+                self.inline.push_str(&text);
+            }
+            Event::Code(code) => {
+                let code = style(format!("`{code}`")).color256(180).to_string();
+                self.inline.push_str(&code);
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                self.inline.push_str(&html)
+            }
+            Event::SoftBreak => self.inline.push(' '),
+            Event::HardBreak => self.flush_inline(),
+            Event::Rule => {
+                self.block_start();
+                let rule = theme().glyph("─", "-").repeat(self.width);
+                self.lines.push(style(rule).dim().to_string());
+            }
+            Event::TaskListMarker(checked) => {
+                self.inline.push_str(if checked { "[x] " } else { "[ ] " })
+            }
+            _ => (),
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.block_start(),
+            Tag::Heading { level, .. } => {
+                self.block_start();
+                self.heading = Some(level);
+            }
+            Tag::BlockQuote(_) => {
+                self.block_start();
+                self.quote_depth += 1;
+            }
+            Tag::CodeBlock(kind) => {
+                self.block_start();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => lang.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code_block = Some(CodeBlock {
+                    lang,
+                    code: String::new(),
+                });
+            }
+            Tag::List(start) => {
+                // NOTE: A nested list is part of the item (no blank line)
+                if self.lists.is_empty() {
+                    self.block_start();
+                } else {
+                    self.flush_inline();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush_inline();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => format!("{} ", theme().glyph("•", "-")),
+                };
+                self.item_marker = Some(marker);
+            }
+            Tag::Table(_) => {
+                self.block_start();
+                self.table = Some(Table::default());
+            }
+            Tag::Emphasis => self.emphasis += 1,
+            Tag::Strong => self.strong += 1,
+            Tag::Strikethrough => self.strikethrough += 1,
+            Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. } => {
+                self.link_urls.push(dest_url.to_string())
+            }
+            _ => (),
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::Item => self.flush_inline(),
+            TagEnd::Heading(_) => {
+                self.flush_inline();
+                self.heading = None;
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_inline();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            }
+            TagEnd::List(_) => {
+                self.flush_inline();
+                self.lists.pop();
+            }
+            TagEnd::Emphasis => self.emphasis = self.emphasis.saturating_sub(1),
+            TagEnd::Strong => self.strong = self.strong.saturating_sub(1),
+            TagEnd::Strikethrough => {
+                self.strikethrough = self.strikethrough.saturating_sub(1)
+            }
+            TagEnd::Link | TagEnd::Image => {
+                if let Some(url) = self.link_urls.pop() {
+                    // NOTE: Autolinks (<https://..>) already show the url
+                    if !self.inline.ends_with(url.as_str()) {
+                        let url = style(format!(" ({url})")).dim().to_string();
+                        self.inline.push_str(&url);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // -- Blocks
+
+    /// Separates the blocks with one blank line.
+    fn block_start(&mut self) {
+        self.flush_inline();
+        // NOTE: Tight list items are not separated
+        let in_tight_item = self.item_marker.is_some();
+        if !in_tight_item && self.lines.last().is_some_and(|l| !l.trim().is_empty())
+        {
+            self.lines.push(String::new());
+        }
+    }
+
+    /// Wraps the current inline text into lines (with the block prefixes).
+    fn flush_inline(&mut self) {
+        if self.inline.trim().is_empty() {
+            self.inline.clear();
+            return;
+        }
+        let inline = std::mem::take(&mut self.inline);
+
+        let (first_prefix, prefix) = self.prefixes();
+        let text = match self.heading {
+            Some(HeadingLevel::H1) => {
+                style(inline).bold().underlined().fg(theme().accent)
+            }
+            Some(_) => style(inline).bold().fg(theme().accent),
+            None => style(inline),
+        }
+        .to_string();
+
+        let options = textwrap::Options::new(self.width)
+            .initial_indent(&first_prefix)
+            .subsequent_indent(&prefix);
+        for line in textwrap::wrap(&text, options) {
+            self.push_prose(&line);
+        }
+    }
+
+    fn end_code_block(&mut self) {
+        let Some(CodeBlock { lang, code }) = self.code_block.take() else {
+            return;
+        };
+        let (_, prefix) = self.prefixes();
+
+        let fence = style(format!("```{lang}")).dim().to_string();
+        self.lines.push(format!("{prefix}{fence}"));
+        for line in highlight(&code, &lang) {
+            self.lines.push(format!("{prefix}{line}"));
+        }
+        self.lines.push(format!("{prefix}{}", style("```").dim()));
+    }
+
+    fn end_table(&mut self) {
+        let Some(table) = self.table.take() else {
+            return;
+        };
+        let (_, prefix) = self.prefixes();
+
+        // -- Column widths
+        let mut widths: Vec<usize> = Vec::new();
+        for row in table.rows.iter() {
+            for (idx, cell) in row.iter().enumerate() {
+                let cell_width = measure_text_width(cell);
+                match widths.get_mut(idx) {
+                    Some(width) => *width = (*width).max(cell_width),
+                    None => widths.push(cell_width),
+                }
+            }
+        }
+
+        // -- Rows (the head ones in bold, then a separator)
+        for (row_idx, row) in table.rows.iter().enumerate() {
+            let cells: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(idx, width)| {
+                    let cell = row.get(idx).map(String::as_str).unwrap_or_default();
+                    let pad = " ".repeat(width - measure_text_width(cell));
+                    if row_idx < table.head_rows {
+                        format!("{}{pad}", style(cell).bold())
+                    } else {
+                        format!("{cell}{pad}")
+                    }
+                })
+                .collect();
+            let sep = format!(" {} ", theme().glyph("│", "|"));
+            self.push_prose(&format!("{prefix}{}", cells.join(&sep)));
+
+            if row_idx + 1 == table.head_rows {
+                let rules: Vec<String> = widths
+                    .iter()
+                    .map(|w| theme().glyph("─", "-").repeat(*w))
+                    .collect();
+                let rule = style(rules.join(theme().glyph("─┼─", "-+-"))).dim();
+                self.lines.push(format!("{prefix}{rule}"));
+            }
+        }
+    }
+
+    // -- Support
+
+    /// Pushes a prose `line`, annotated (the code lines are pushed as is).
+    fn push_prose(&mut self, line: &str) {
+        match self.annotate {
+            Some(annotate) => self.lines.push(annotate(line)),
+            None => self.lines.push(line.to_string()),
+        }
+    }
+
+    /// The prefix of the first line and of the next lines of the current
+    /// block (quotes bar, list indent and item marker).
+    fn prefixes(&mut self) -> (String, String) {
+        let quote = style(theme().glyph("│ ", "> "))
+            .dim()
+            .to_string()
+            .repeat(self.quote_depth);
+        let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+        match self.item_marker.take() {
+            Some(marker) => {
+                let pad = " ".repeat(measure_text_width(&marker));
+                (
+                    format!("{quote}{indent}{marker}"),
+                    format!("{quote}{indent}{pad}"),
+                )
+            }
+            None => {
+                let pad = if self.lists.is_empty() { "" } else { "  " };
+                let prefix = format!("{quote}{indent}{pad}");
+                (prefix.clone(), prefix)
+            }
+        }
+    }
+
+    fn inline_style(&self) -> Style {
+        let mut inline_style = Style::new();
+        if self.strong > 0 {
+            inline_style = inline_style.bold();
+        }
+        if self.emphasis > 0 {
+            inline_style = inline_style.italic();
+        }
+        if self.strikethrough > 0 {
+            inline_style = inline_style.strikethrough();
+        }
+        if !self.link_urls.is_empty() {
+            inline_style = inline_style.underlined().fg(theme().accent);
+        }
+        inline_style
+    }
+}
+
+// endregion:    -- Renderer
+
+// region:       -- Code Highlight
+
+/// The highlighted lines of the `code` (as is when colors are disabled).
+fn highlight(code: &str, lang: &str) -> Vec<String> {
+    if !console::colors_enabled() {
+        return code.lines().map(String::from).collect();
+    }
+
+    let (syntax_set, theme) = syntect_assets();
+    let syntax = syntax_set
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    LinesWithEndings::from(code)
+        .map(|line| match highlighter.highlight_line(line, syntax_set) {
+            Ok(ranges) => {
+                let line = as_24_bit_terminal_escaped(&ranges, false);
+                format!("{}\x1b[0m", line.trim_end_matches(['\n', '\r']))
+            }
+            Err(_) => line.trim_end_matches(['\n', '\r']).to_string(),
+        })
+        .collect()
+}
+
+/// The syntaxes and theme, loaded once (it takes a few milliseconds).
+fn syntect_assets() -> &'static (SyntaxSet, Theme) {
+    static ASSETS: OnceLock<(SyntaxSet, Theme)> = OnceLock::new();
+    ASSETS.get_or_init(|| {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let mut themes = ThemeSet::load_defaults().themes;
+        let theme = themes.remove(CODE_THEME).unwrap_or_default();
+        (syntax_set, theme)
+    })
+}
+
+// endregion:    -- Code Highlight
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    #[test]
+    fn test_render_annotates_prose_only() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_markdown = "Say 你好\n\n```rust\nlet hi = \"你好\";\n```\n";
+        let fx_annotate: fn(&str) -> String = |line| format!("<{line}>");
+
+        // -- Exec
+        let rendered = render(fx_markdown, 80, Some(fx_annotate));
+
+        // -- Check
+        let lines: Vec<String> = rendered
+            .lines()
+            .map(|line| console::strip_ansi_codes(line).to_string())
+            .collect();
+        assert_eq!(lines[0], "<Say 你好>");
+        let code_line = lines.iter().find(|line| line.contains("let hi"));
+        assert_eq!(code_line.map(String::as_str), Some("let hi = \"你好\";"));
+        assert_eq!(lines.iter().filter(|line| line.contains('<')).count(), 1);
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
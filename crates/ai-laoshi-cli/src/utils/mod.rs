@@ -1,4 +1,18 @@
 // region:       -- Modules
 pub mod cli;
+pub mod code;
+pub mod dict;
+pub mod editor;
+pub mod grammar;
+pub mod level;
+pub mod markdown;
+pub mod plan;
+pub mod profile;
+pub mod quiz;
+pub mod reader;
+pub mod status;
+pub mod theme;
+pub mod tutor;
+pub mod vocab;
 
 // endregion:    -- Modules
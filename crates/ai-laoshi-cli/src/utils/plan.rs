@@ -0,0 +1,113 @@
+// NOTE: Terraform-like display of the `laoshi plan` changes.
+use crate::utils::theme::theme;
+use ai_laoshi_core::{Change, Plan, UploadReason};
+use console::{style, StyledObject};
+use similar::{ChangeTag, TextDiff};
+
+// region:       -- Print Plan
+
+pub fn print_plan(plan: &Plan) {
+    if plan.is_empty() {
+        println!("No changes. Laoshi {} is up-to-date.", plan.name());
+        return;
+    }
+
+    println!("Laoshi {} changes:\n", plan.name());
+
+    let (mut num_add, mut num_change, mut num_destroy) = (0, 0, 0);
+    for change in plan.changes() {
+        match change {
+            Change::CreateAssistant { name, model } => {
+                num_add += 1;
+                println!("  {} assistant \"{name}\" (model \"{model}\")", sym_add());
+            }
+            Change::UpdateAssistant { field, from, to } => {
+                num_change += 1;
+                println!("  {} {field}: \"{from}\" -> \"{to}\"", sym_change());
+            }
+            Change::UpdateInstructions { from, to } => {
+                num_change += 1;
+                println!("  {} instructions:", sym_change());
+                print_text_diff(from, to);
+            }
+            Change::UploadBundle {
+                bundle_name,
+                reason,
+            } => {
+                let (sym, reason) = match reason {
+                    UploadReason::New => {
+                        num_add += 1;
+                        (sym_add(), "new")
+                    }
+                    UploadReason::Changed => {
+                        num_change += 1;
+                        (sym_change(), "changed")
+                    }
+                    UploadReason::Untracked => {
+                        num_change += 1;
+                        (sym_change(), "untracked, re-upload")
+                    }
+                };
+                println!("  {sym} bundle \"{bundle_name}\" ({reason})");
+            }
+            Change::DeleteFile { file_name } => {
+                num_destroy += 1;
+                println!("  {} file \"{file_name}\"", sym_destroy());
+            }
+        }
+    }
+
+    println!(
+        "\nPlan: {num_add} to add, {num_change} to change, {num_destroy} to destroy."
+    );
+}
+
+/// Prints the changed lines (with 2 lines of context) of the `from` -> `to` text.
+fn print_text_diff(from: &str, to: &str) {
+    let diff = TextDiff::from_lines(from, to);
+    for (idx, group) in diff.grouped_ops(2).iter().enumerate() {
+        if idx > 0 {
+            println!("      {}", style("...").dim());
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches('\n');
+                match change.tag() {
+                    ChangeTag::Delete => {
+                        println!(
+                            "      {}",
+                            style(format!("- {line}")).fg(theme().error)
+                        )
+                    }
+                    ChangeTag::Insert => {
+                        println!(
+                            "      {}",
+                            style(format!("+ {line}")).fg(theme().success)
+                        )
+                    }
+                    ChangeTag::Equal => {
+                        println!("      {}", style(format!("  {line}")).dim())
+                    }
+                }
+            }
+        }
+    }
+}
+
+// endregion:    -- Print Plan
+
+// region:       -- Symbols
+
+fn sym_add() -> StyledObject<&'static str> {
+    style("+").fg(theme().success)
+}
+
+fn sym_change() -> StyledObject<&'static str> {
+    style("~").fg(theme().warning)
+}
+
+fn sym_destroy() -> StyledObject<&'static str> {
+    style("-").fg(theme().error)
+}
+
+// endregion:    -- Symbols
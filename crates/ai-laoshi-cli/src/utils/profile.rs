@@ -0,0 +1,61 @@
+// NOTE: The `/profile` display and $EDITOR edit of the learner profile (kept
+// in laoshi/.laoshi/profile.toml, sent with each run, see `LearnerProfile`).
+use crate::utils::cli::{edit_text, icon_err};
+use crate::Result;
+use ai_laoshi_core::{LearnerProfile, PROFILE_FIELDS};
+use console::style;
+
+const PROFILE_HINT: &str = "\
+# The learner profile, sent with each message (the assistant is unchanged).
+# e.g.,
+# level = \"HSK 3\"
+# goals = \"Business meetings, reading the news\"
+# native_language = \"French\"
+# script = \"traditional\"   # or \"simplified\"
+# avoid_topics = [\"politics\"]
+";
+
+pub fn print_profile(profile: &LearnerProfile) {
+    if profile.is_empty() {
+        println!("No learner profile yet. Set it with /profile edit or");
+        println!("/profile <field> <value> ({}).", PROFILE_FIELDS.join(", "));
+        return;
+    }
+
+    let value = |value: Option<&str>| match value {
+        Some(value) => value.to_string(),
+        None => style("-").dim().to_string(),
+    };
+    let avoid_topics = profile.avoid_topics.join(", ");
+    let rows = [
+        ("level", value(profile.level.as_deref())),
+        ("goals", value(profile.goals.as_deref())),
+        ("native_language", value(profile.native_language.as_deref())),
+        ("script", value(profile.script.map(|s| s.name()))),
+        (
+            "avoid_topics",
+            value((!avoid_topics.is_empty()).then_some(avoid_topics.as_str())),
+        ),
+    ];
+    for (field, value) in rows {
+        println!("{:<16} {value}", style(field).bold());
+    }
+}
+
+/// Edits the `profile` in $EDITOR. Returns `None` if not saved, unchanged,
+/// or invalid (reported).
+pub fn edit_profile(profile: &LearnerProfile) -> Result<Option<LearnerProfile>> {
+    let content = format!("{PROFILE_HINT}\n{}", profile.to_toml());
+    let Some(edited) = edit_text(&content, ".toml")? else {
+        return Ok(None);
+    };
+
+    match LearnerProfile::from_toml(&edited) {
+        Ok(edited) if edited == *profile => Ok(None),
+        Ok(edited) => Ok(Some(edited)),
+        Err(e) => {
+            println!("{} Invalid profile, not saved. {e}", icon_err());
+            Ok(None)
+        }
+    }
+}
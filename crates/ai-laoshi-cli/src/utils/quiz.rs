@@ -0,0 +1,146 @@
+// NOTE: The `/quiz` loop (one question at a time, graded by the laoshi) and
+// the `laoshi quiz stats` display. An empty answer skips the question (as
+// does a grading error), Ctrl-D ends the quiz (the answered questions are
+// still scored).
+use crate::utils::cli::{icon_check, icon_err};
+use crate::utils::editor::LineEditor;
+use crate::utils::theme::theme;
+use crate::Result;
+use ai_laoshi_core::{Laoshi, Quiz, QuizScores};
+use console::style;
+use std::path::Path;
+
+/// Runs a quiz and records its score in the laoshi `dir`.
+pub async fn run_quiz(
+    dir: &Path,
+    laoshi: &Laoshi,
+    editor: &mut LineEditor,
+    topic: Option<&str>,
+    num_questions: usize,
+) -> Result<()> {
+    let mut quiz = laoshi.new_quiz(topic, num_questions).await?;
+    let total = quiz.questions.len();
+    println!(
+        "{} {total} questions{} (empty answer skips, Ctrl-D ends)",
+        icon_check(),
+        topic.map(|t| format!(" on {t}")).unwrap_or_default()
+    );
+
+    // NOTE: The quiz thread is deleted whatever the outcome (Ctrl-D, errors)
+    let scores = ask_questions(laoshi, editor, &mut quiz).await;
+    let result =
+        scores.map(|scores| (!scores.is_empty()).then(|| quiz.result(&scores)));
+    laoshi.end_quiz(quiz).await;
+
+    let Some(result) = result? else {
+        println!("Quiz ended, no score recorded.");
+        return Ok(());
+    };
+    // NOTE: The unanswered questions (Ctrl-D) count as wrong
+    println!();
+    println!(
+        "{} Score: {:.1}/{} ({:.0}%)",
+        icon_check(),
+        result.score,
+        result.num_questions,
+        result.percent()
+    );
+    QuizScores::load_from_dir(dir)?.record(result)?;
+
+    Ok(())
+}
+
+/// Asks the questions of the `quiz`, returns the scores of the answered ones.
+async fn ask_questions(
+    laoshi: &Laoshi,
+    editor: &mut LineEditor,
+    quiz: &mut Quiz,
+) -> Result<Vec<f32>> {
+    let total = quiz.questions.len();
+    let mut scores: Vec<f32> = Vec::new();
+    for idx in 0..total {
+        let question = &quiz.questions[idx];
+        println!();
+        println!(
+            "{} {}",
+            style(format!("[{}/{total}]", idx + 1)).fg(theme().accent),
+            style(&question.question).bold()
+        );
+
+        let Some(answer) = editor.read("Answer")? else {
+            break;
+        };
+        if answer.trim().is_empty() {
+            println!("{} Skipped. Expected: {}", icon_err(), question.answer);
+            scores.push(0.0);
+            continue;
+        }
+
+        // NOTE: A grading error does not end the quiz (the question scores 0)
+        let grade = match laoshi.grade_quiz_answer(quiz, idx, &answer).await {
+            Ok(grade) => grade,
+            Err(e) => {
+                println!(
+                    "{} Not graded ({e}). Expected: {}",
+                    icon_err(),
+                    quiz.questions[idx].answer
+                );
+                scores.push(0.0);
+                continue;
+            }
+        };
+        let icon = match grade.correct {
+            true => icon_check(),
+            false => icon_err(),
+        };
+        println!(
+            "{icon} {} {}",
+            style(format!("{:.0}%", grade.score * 100.0)).bold(),
+            grade.explanation
+        );
+        scores.push(grade.score);
+    }
+
+    Ok(scores)
+}
+
+/// e.g.,
+/// topic            attempts  last  best  average  last quiz
+/// error handling          3   80%   80%      67%  2026-10-18
+pub fn print_quiz_stats(dir: &Path) -> Result<()> {
+    let scores = QuizScores::load_from_dir(dir)?;
+    let stats = scores.stats();
+    if stats.is_empty() {
+        println!("No quiz yet. Start one with /quiz [topic] [n] in `laoshi`.");
+        return Ok(());
+    }
+
+    let width = stats
+        .iter()
+        .map(|s| s.topic.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max(5);
+    println!(
+        "{}",
+        style(format!(
+            "{:<width$}  attempts  last  best  average  last quiz",
+            "topic"
+        ))
+        .bold()
+    );
+    for s in stats {
+        println!(
+            "{:<width$}  {:>8}  {:>3.0}%  {:>3.0}%  {:>6.0}%  {}",
+            s.topic,
+            s.attempts,
+            s.last_percent,
+            s.best_percent,
+            s.average_percent,
+            s.last_date.format("%Y-%m-%d")
+        );
+    }
+    println!("\n{} quizzes in total", scores.results().len());
+
+    Ok(())
+}
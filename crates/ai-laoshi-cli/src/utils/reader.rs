@@ -0,0 +1,113 @@
+// NOTE: The `/reader <topic>` graded reader: the story of the laoshi is
+// checked offline against the known words (the vocabulary deck, and the
+// words up to the learner level, HSK 1 if not set), rewritten while too
+// hard (up to `MAX_READER_REWRITES`), then printed and saved with its
+// glossary.
+use crate::utils::cli::{icon_check, icon_err, print_res};
+use crate::utils::tutor::{cedict, hsk_level, hsk_lists, tutor_err_msg};
+use crate::Result;
+use ai_laoshi_core::chinese::{is_above, HskLists};
+use ai_laoshi_core::{Deck, Laoshi, MAX_READER_REWRITES};
+use console::style;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Writes, checks and saves a reader on the `topic` in the laoshi `dir`.
+pub async fn run_reader(
+    dir: &Path,
+    laoshi: &Laoshi,
+    topic: &str,
+    raw: bool,
+) -> Result<()> {
+    let cedict = cedict()?;
+    let deck = Deck::load_from_dir(dir)?;
+    // NOTE: The HSK lists are optional (the deck alone if none). Without a
+    //       learner level, the HSK 1 words are known (e.g., 我, 是, 的)
+    let (level, _) = hsk_level();
+    let lists: Option<(u8, Arc<HskLists>)> = match hsk_lists() {
+        Ok(lists) => Some((level.unwrap_or(1), lists)),
+        Err(e) => {
+            if level.is_some() {
+                println!("{} {}", icon_err(), tutor_err_msg(&e));
+            }
+            None
+        }
+    };
+    let has_known_words = !deck.is_empty() || lists.is_some();
+    if !has_known_words {
+        println!(
+            "No known words yet (save some with /save, or set your level \
+             with /profile level), the reader is not checked."
+        );
+    }
+
+    // NOTE: Without lists, the single chars are known (the deck has none, so
+    //       the function words would always be unknown)
+    let is_known = |word: &str| {
+        deck.contains(word)
+            || match &lists {
+                Some((known_level, lists)) => {
+                    !is_above(lists.level(word, &cedict), *known_level)
+                }
+                None => word.chars().count() == 1,
+            }
+    };
+
+    // -- Write the reader (rewritten while too hard)
+    let level_name = lists
+        .as_ref()
+        .filter(|_| level.is_some())
+        .map(|(known_level, lists)| lists.version().level_name(*known_level));
+    let known_words: Vec<&str> = deck
+        .cards()
+        .iter()
+        .map(|card| card.simplified.as_str())
+        .collect();
+    let mut reader = laoshi
+        .new_reader(topic, level_name.as_deref(), &known_words)
+        .await?;
+    let mut check = reader.check(&cedict, is_known);
+    while has_known_words
+        && check.too_hard()
+        && reader.rewrites < MAX_READER_REWRITES
+    {
+        println!(
+            "{}",
+            style(format!(
+                "{:.0}% unknown words, asking for a simpler rewrite ({}/{MAX_READER_REWRITES})...",
+                check.unknown_share * 100.0,
+                reader.rewrites + 1
+            ))
+            .dim()
+        );
+        // NOTE: A failed rewrite keeps the current story
+        if let Err(e) = laoshi.rewrite_reader(&mut reader, &check.unknown).await {
+            println!("{} {e}", icon_err());
+            break;
+        }
+        check = reader.check(&cedict, is_known);
+    }
+    laoshi.end_reader(&reader).await;
+
+    // -- Print and save
+    print_res(&reader.story, raw);
+    println!();
+    if has_known_words {
+        let icon = match check.too_hard() {
+            true => icon_err(),
+            false => icon_check(),
+        };
+        println!(
+            "{icon} {} words, {:.0}% known",
+            check.num_words,
+            (1.0 - check.unknown_share) * 100.0
+        );
+        if !check.unknown.is_empty() {
+            println!("New words: {}", check.unknown.join(", "));
+        }
+    }
+    let file = reader.save(dir, &check.unknown, &cedict)?;
+    println!("{} Saved to {}", icon_check(), file.display());
+
+    Ok(())
+}
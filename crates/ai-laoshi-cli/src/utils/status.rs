@@ -0,0 +1,89 @@
+// NOTE: Display of the `/status` and `laoshi status` info.
+use crate::utils::theme::theme;
+use ai_laoshi_core::Status;
+use console::style;
+
+const LABEL_WIDTH: usize = 13;
+
+pub fn print_status(status: &Status) {
+    println!("Laoshi {}", style(&status.name).bold());
+
+    match status.assistant_id.as_deref() {
+        Some(assistant_id) => {
+            print_field("assistant", &format!("{assistant_id} ({})", status.model))
+        }
+        None => print_field(
+            "assistant",
+            &format!("not created (see `laoshi apply`), model {}", status.model),
+        ),
+    }
+
+    let ix = match status.instructions_hash.as_deref() {
+        Some(hash) => format!("sha256:{}", &hash[..12]),
+        None => "none".to_string(),
+    };
+    if status.instructions_changed {
+        print_field(
+            "instructions",
+            &format!(
+                "{ix} {}",
+                style("(local changes, see `laoshi plan`)").fg(theme().warning)
+            ),
+        );
+    } else {
+        print_field("instructions", &ix);
+    }
+
+    print_field("thread", status.thread_id.as_deref().unwrap_or("none"));
+
+    if status.bundles.is_empty() {
+        print_field("bundles", "none");
+    } else {
+        print_field("bundles", "");
+        let name_width = status
+            .bundles
+            .iter()
+            .map(|b| b.bundle_name.len())
+            .max()
+            .unwrap_or_default();
+        for bundle in status.bundles.iter() {
+            let file_id = match bundle.file_id.as_deref() {
+                Some(file_id) => style(file_id.to_string()),
+                None => style("not uploaded".to_string()).fg(theme().warning),
+            };
+            println!(
+                "    {:<name_width$}  {:>4} files  {:>9}  {file_id}",
+                bundle.bundle_name,
+                bundle.num_files,
+                fmt_size(bundle.size),
+            );
+        }
+    }
+
+    if let Some(usage) = status.usage {
+        print_field(
+            "tokens",
+            &format!(
+                "{} (prompt {}, completion {})",
+                usage.total_tokens, usage.prompt_tokens, usage.completion_tokens
+            ),
+        );
+    }
+}
+
+// region:       -- Support
+
+fn print_field(label: &str, value: &str) {
+    println!("  {:<LABEL_WIDTH$} {value}", style(label).dim());
+}
+
+/// e.g., "812 B", "48.2 KB", "1.3 MB"
+fn fmt_size(size: u64) -> String {
+    match size {
+        0..=1023 => format!("{size} B"),
+        1024..=1_048_575 => format!("{:.1} KB", size as f64 / 1024.0),
+        _ => format!("{:.1} MB", size as f64 / 1_048_576.0),
+    }
+}
+
+// endregion:    -- Support
@@ -0,0 +1,124 @@
+// NOTE: The CLI theme: colors, icons and prompt text from the [theme] config,
+// set once at startup (see `init_theme`). Plain mode (no colors) is automatic
+// when stdout is not a terminal or NO_COLOR is set, and ASCII mode replaces
+// the unicode glyphs (icons, bullets, rules) for screen readers.
+use ai_laoshi_core::{ThemeColor, ThemeConfig};
+use console::Color;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+const DEFAULT_PROMPT: &str = "Ask away!";
+const ACCENT_COLOR: u8 = 45;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+#[derive(Debug)]
+pub struct Theme {
+    pub prompt: String,
+    pub ascii: bool,
+    /// No colors (NO_COLOR, or stdout not a terminal)
+    pub plain: bool,
+    pub accent: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub icons: Icons,
+}
+
+#[derive(Debug)]
+pub struct Icons {
+    pub res: String,
+    pub check: String,
+    pub uploading: String,
+    pub uploaded: String,
+    pub deleted: String,
+    pub err: String,
+}
+
+/// Sets the theme from the `config` (once, the first call wins).
+pub fn init_theme(config: ThemeConfig) {
+    let plain = !std::io::stdout().is_terminal()
+        || std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty());
+    // NOTE: Disables all the console styles (icons, markdown, prompts)
+    if plain {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+
+    let _ = THEME.set(Theme::new(config, plain));
+}
+
+/// The current theme (the default one if `init_theme` was not called).
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(|| Theme::new(ThemeConfig::default(), false))
+}
+
+impl Theme {
+    fn new(config: ThemeConfig, plain: bool) -> Self {
+        let ThemeConfig {
+            ascii,
+            prompt,
+            colors,
+            icons,
+        } = config;
+
+        // NOTE: The configured icons win over the ASCII ones
+        let icon = |configured: Option<String>, unicode: &str, ascii_icon: &str| {
+            configured.unwrap_or_else(|| match ascii {
+                true => ascii_icon.to_string(),
+                false => unicode.to_string(),
+            })
+        };
+
+        Theme {
+            prompt: prompt.unwrap_or_else(|| DEFAULT_PROMPT.to_string()),
+            ascii,
+            plain,
+            accent: to_color(colors.accent, Color::Color256(ACCENT_COLOR)),
+            success: to_color(colors.success, Color::Green),
+            warning: to_color(colors.warning, Color::Yellow),
+            error: to_color(colors.error, Color::Red),
+            icons: Icons {
+                res: icon(icons.res, "➤", ">"),
+                check: icon(icons.check, "✔", "[ok]"),
+                uploading: icon(icons.uploading, "↥", "[..]"),
+                uploaded: icon(icons.uploaded, "↥", "[up]"),
+                deleted: icon(icons.deleted, "⌫", "[del]"),
+                err: icon(icons.err, "✗", "[err]"),
+            },
+        }
+    }
+
+    /// The glyph, or its ASCII replacement in ASCII mode.
+    pub fn glyph<'a>(&self, unicode: &'a str, ascii: &'a str) -> &'a str {
+        if self.ascii {
+            ascii
+        } else {
+            unicode
+        }
+    }
+}
+
+// region:       -- Support
+
+/// The console color of the config `color` (`default` if none or unknown).
+fn to_color(color: Option<ThemeColor>, default: Color) -> Color {
+    match color {
+        Some(ThemeColor::Index(idx)) => Color::Color256(idx),
+        Some(ThemeColor::Name(name)) => match name.to_lowercase().as_str() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            // NOTE: A quoted index (e.g., accent = "45")
+            other => other.parse().map(Color::Color256).unwrap_or(default),
+        },
+        None => default,
+    }
+}
+
+// endregion:    -- Support
@@ -0,0 +1,343 @@
+// NOTE: The tutor display: annotates the Chinese words of the responses with
+// their pinyin (see `ai_laoshi_core::chinese`), as ruby lines above the hanzi
+// or in brackets after them. Set from the [tutor] config at startup, changed
+// with `/pinyin`. The CC-CEDICT file is loaded on the first use (~10 MB),
+// also used by the vocabulary deck (see `utils::vocab`) and, with the HSK
+// word lists, by the level analysis (see `utils::level`), and by the
+// Simplified/Traditional conversion of the responses (the learner profile
+// script, or `/script`; display only, the transcript keeps the original).
+use crate::utils::theme::theme;
+use crate::{Error, Result};
+use ai_laoshi_core::chinese::{
+    annotate, Cedict, HskLists, HskVersion, Script, ScriptConverter, ToneStyle,
+};
+use ai_laoshi_core::{LearnerProfile, PinyinDisplay, TutorConfig};
+use console::{measure_text_width, style};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const CEDICT_URL: &str = "https://www.mdbg.net/chinese/dictionary?page=cc-cedict";
+
+static TUTOR: Mutex<Tutor> = Mutex::new(Tutor {
+    display: PinyinDisplay::Off,
+    tones: ToneStyle::Marks,
+    cedict_file: PathBuf::new(),
+    cedict: None,
+    auto_save: false,
+    hsk_level: None,
+    profile_level: None,
+    hsk_version: HskVersion::V2,
+    hsk_dir: PathBuf::new(),
+    hsk_lists: None,
+    hsk_rewrite: false,
+    correct: false,
+    script: None,
+    opencc_dir: PathBuf::new(),
+    converter: None,
+});
+
+struct Tutor {
+    display: PinyinDisplay,
+    tones: ToneStyle,
+    cedict_file: PathBuf,
+    cedict: Option<Arc<Cedict>>,
+    auto_save: bool,
+    hsk_level: Option<u8>,
+    /// The HSK level of the learner profile, over the `hsk_level`
+    profile_level: Option<u8>,
+    hsk_version: HskVersion,
+    hsk_dir: PathBuf,
+    hsk_lists: Option<Arc<HskLists>>,
+    hsk_rewrite: bool,
+    correct: bool,
+    /// `None` shows the responses as is
+    script: Option<Script>,
+    opencc_dir: PathBuf,
+    converter: Option<Arc<ScriptConverter>>,
+}
+
+/// A `/pinyin` setting.
+#[derive(Debug, Clone, Copy)]
+pub enum PinyinSetting {
+    Display(PinyinDisplay),
+    Tones(ToneStyle),
+}
+
+/// Sets the tutor display from the [tutor] `config` of the laoshi `dir`
+/// (the CC-CEDICT is not loaded yet, see `load_cedict`).
+pub fn init_tutor(dir: impl AsRef<Path>, config: TutorConfig) {
+    if let Ok(mut tutor) = TUTOR.lock() {
+        tutor.cedict_file = config.cedict_path(&dir);
+        tutor.hsk_dir = config.hsk_path(&dir);
+        tutor.opencc_dir = config.opencc_path(&dir);
+        tutor.display = config.pinyin;
+        tutor.tones = config.tones;
+        tutor.auto_save = config.auto_save;
+        tutor.hsk_level = config.hsk_level;
+        tutor.hsk_version = config.hsk_version;
+        tutor.hsk_rewrite = config.hsk_rewrite;
+        tutor.correct = config.correct;
+    }
+}
+
+/// Loads the CC-CEDICT if the pinyin display is on (turned off if it fails).
+pub fn load_cedict() -> Result<()> {
+    if pinyin_display() == PinyinDisplay::Off {
+        return Ok(());
+    }
+    if let Err(e) = cedict() {
+        if let Ok(mut tutor) = TUTOR.lock() {
+            tutor.display = PinyinDisplay::Off;
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// The CC-CEDICT (loaded on the first call).
+pub fn cedict() -> Result<Arc<Cedict>> {
+    let mut tutor = TUTOR.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cedict) = &tutor.cedict {
+        return Ok(cedict.clone());
+    }
+    let cedict = Arc::new(Cedict::load(&tutor.cedict_file)?);
+    tutor.cedict = Some(cedict.clone());
+    Ok(cedict)
+}
+
+/// The HSK word lists of the [tutor] hsk_version (loaded on the first call).
+pub fn hsk_lists() -> Result<Arc<HskLists>> {
+    let mut tutor = TUTOR.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(lists) = &tutor.hsk_lists {
+        return Ok(lists.clone());
+    }
+    let lists = Arc::new(HskLists::load(&tutor.hsk_dir, tutor.hsk_version)?);
+    tutor.hsk_lists = Some(lists.clone());
+    Ok(lists)
+}
+
+/// The script conversion tables (loaded on the first call, with the
+/// CC-CEDICT).
+fn script_converter() -> Result<Arc<ScriptConverter>> {
+    if let Some(converter) = TUTOR.lock().ok().and_then(|t| t.converter.clone()) {
+        return Ok(converter);
+    }
+    let cedict = cedict()?;
+    let mut tutor = TUTOR.lock().unwrap_or_else(|e| e.into_inner());
+    let mut converter = ScriptConverter::from_cedict(&cedict);
+    converter.add_opencc_tables(&tutor.opencc_dir);
+    let converter = Arc::new(converter);
+    tutor.converter = Some(converter.clone());
+    Ok(converter)
+}
+
+/// Sets the learner level and the script of the learner `profile` (see
+/// `hsk_level` and `set_script`).
+pub fn apply_profile(profile: &LearnerProfile) -> Result<()> {
+    if let Ok(mut tutor) = TUTOR.lock() {
+        tutor.profile_level = profile.hsk_level();
+    }
+    set_script(profile.script)
+}
+
+/// Sets the `script` of the responses (loads the conversion tables if needed,
+/// stays as is if it fails).
+pub fn set_script(script: Option<Script>) -> Result<()> {
+    if script.is_some() {
+        script_converter()?;
+    }
+    if let Ok(mut tutor) = TUTOR.lock() {
+        tutor.script = script;
+    }
+    Ok(())
+}
+
+/// e.g., "Script: traditional (converted)"
+pub fn script_status() -> String {
+    match TUTOR.lock().ok().and_then(|tutor| tutor.script) {
+        Some(script) => format!("Script: {} (converted)", script.name()),
+        None => "Script: as written by the laoshi".to_string(),
+    }
+}
+
+/// The `text` in the script of the learner (as is if none).
+pub fn convert_script(text: &str) -> String {
+    let (script, converter) = match TUTOR.lock() {
+        Ok(tutor) => (tutor.script, tutor.converter.clone()),
+        Err(_) => return text.to_string(),
+    };
+    match (script, converter) {
+        (Some(script), Some(converter)) => converter.convert(text, script),
+        _ => text.to_string(),
+    }
+}
+
+/// The learner HSK level, and `true` if the responses above it are
+/// rewritten (the [tutor] hsk_rewrite).
+// NOTE: The HSK level of the learner profile wins, the [tutor] hsk_level
+//       is the default (e.g., no profile level, or "beginner")
+pub fn hsk_level() -> (Option<u8>, bool) {
+    TUTOR
+        .lock()
+        .map(|tutor| {
+            let level = tutor.profile_level.or(tutor.hsk_level);
+            (level, tutor.hsk_rewrite)
+        })
+        .unwrap_or_default()
+}
+
+/// `true` if the new words of the responses go to the vocabulary deck.
+pub fn auto_save() -> bool {
+    TUTOR.lock().is_ok_and(|tutor| tutor.auto_save)
+}
+
+/// `true` if the Chinese of the learner messages is corrected (the [tutor]
+/// correct, or `/correct`).
+pub fn correct_mode() -> bool {
+    TUTOR.lock().is_ok_and(|tutor| tutor.correct)
+}
+
+pub fn set_correct_mode(correct: bool) {
+    if let Ok(mut tutor) = TUTOR.lock() {
+        tutor.correct = correct;
+    }
+}
+
+/// The tone style of the pinyin (the [tutor] tones, or `/pinyin`).
+pub fn tone_style() -> ToneStyle {
+    TUTOR
+        .lock()
+        .map(|tutor| tutor.tones)
+        .unwrap_or(ToneStyle::Marks)
+}
+
+fn pinyin_display() -> PinyinDisplay {
+    TUTOR
+        .lock()
+        .map(|tutor| tutor.display)
+        .unwrap_or(PinyinDisplay::Off)
+}
+
+/// Applies the `/pinyin` `setting` (loads the CC-CEDICT if needed).
+pub fn set_pinyin(setting: PinyinSetting) -> Result<()> {
+    if let Ok(mut tutor) = TUTOR.lock() {
+        match setting {
+            PinyinSetting::Display(display) => tutor.display = display,
+            PinyinSetting::Tones(tones) => tutor.tones = tones,
+        }
+    }
+    load_cedict()
+}
+
+/// e.g., "Pinyin: ruby, tone marks"
+pub fn pinyin_status() -> String {
+    let Ok(tutor) = TUTOR.lock() else {
+        return String::new();
+    };
+    let display = match tutor.display {
+        PinyinDisplay::Off => "off",
+        PinyinDisplay::Ruby => "ruby (above the hanzi)",
+        PinyinDisplay::Inline => "inline (in brackets)",
+    };
+    let tones = match tutor.tones {
+        ToneStyle::Marks => "tone marks",
+        ToneStyle::Numbers => "tone numbers",
+    };
+    format!("Pinyin: {display}, {tones}")
+}
+
+/// The error message, with where to get the CC-CEDICT if missing.
+pub fn tutor_err_msg(e: &Error) -> String {
+    match e {
+        Error::AILaoshi(ai_laoshi_core::Error::CedictNotFound(file)) => format!(
+            "No CC-CEDICT dictionary at '{file}'.\n  \
+             Download it from {CEDICT_URL} and save it (unzipped) as '{file}'."
+        ),
+        Error::AILaoshi(ai_laoshi_core::Error::HskListsNotFound(dir)) => format!(
+            "No HSK word lists in '{dir}'. Only the HSK 2.0 lists are built in, \
+             the HSK 3.0 ones are not bundled.\n  \
+             Save them as '{dir}/1.txt' ... '{dir}/6.txt' and '{dir}/7-9.txt' \
+             (one word per line)."
+        ),
+        e => format!("{e}"),
+    }
+}
+
+/// Annotates the Chinese words of the `text`, line by line, except in the
+/// fenced code blocks. Returns the `text` as is when the pinyin display is off.
+pub fn annotate_text(text: &str) -> String {
+    // NOTE: A load error was reported by `load_cedict`/`set_pinyin` already
+    let _ = load_cedict();
+    let (display, tones, cedict) = match TUTOR.lock() {
+        Ok(tutor) => (tutor.display, tutor.tones, tutor.cedict.clone()),
+        Err(_) => return text.to_string(),
+    };
+    let Some(cedict) = cedict.filter(|_| display != PinyinDisplay::Off) else {
+        return text.to_string();
+    };
+
+    // NOTE: The code is never annotated (the pinyin would end up in the
+    // copied code, e.g., in the Chinese of a string literal)
+    let mut in_code = false;
+    text.lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                return line.to_string();
+            }
+            match (in_code, display) {
+                (true, _) => line.to_string(),
+                (false, PinyinDisplay::Ruby) => ruby_line(line, &cedict, tones),
+                (false, _) => inline_line(line, &cedict, tones),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// region:       -- Support
+
+/// The pinyin line above the `line` (aligned on the hanzi words).
+fn ruby_line(line: &str, cedict: &Cedict, tones: ToneStyle) -> String {
+    let rubies = annotate(line, cedict, tones);
+    if rubies.iter().all(|ruby| ruby.pinyin.is_none()) {
+        return line.to_string();
+    }
+
+    let (mut top, mut bottom) = (String::new(), String::new());
+    for ruby in rubies {
+        let text_width = measure_text_width(ruby.text);
+        match ruby.pinyin {
+            None => {
+                top.push_str(&" ".repeat(text_width));
+                bottom.push_str(ruby.text);
+            }
+            // NOTE: The wider of the two is padded (one space between words)
+            Some(pinyin) => {
+                let pinyin_width = measure_text_width(&pinyin);
+                let width = text_width.max(pinyin_width + 1);
+                top.push_str(&style(&pinyin).fg(theme().accent).to_string());
+                top.push_str(&" ".repeat(width - pinyin_width));
+                bottom.push_str(ruby.text);
+                bottom.push_str(&" ".repeat(width - text_width));
+            }
+        }
+    }
+
+    format!("{}\n{}", top.trim_end(), bottom.trim_end())
+}
+
+/// The `line` with the pinyin in brackets after each hanzi word.
+fn inline_line(line: &str, cedict: &Cedict, tones: ToneStyle) -> String {
+    annotate(line, cedict, tones)
+        .into_iter()
+        .map(|ruby| match ruby.pinyin {
+            Some(pinyin) => {
+                format!("{}{}", ruby.text, style(format!("({pinyin})")).dim())
+            }
+            None => ruby.text.to_string(),
+        })
+        .collect()
+}
+
+// endregion:    -- Support
@@ -0,0 +1,269 @@
+// NOTE: The vocabulary deck commands: `/save` (and the [tutor] auto_save of
+// the responses), `/review`, the SM-2 spaced repetition in the terminal
+// (see `ai_laoshi_core::Deck`), and the `laoshi vocab` export/import.
+// Progress is saved after each card, so a review can be stopped (q) anytime.
+use crate::utils::cli::{icon_check, icon_err};
+use crate::utils::theme::theme;
+use crate::utils::tutor::{auto_save, cedict, hsk_level, hsk_lists, tutor_err_msg};
+use crate::{Error, Result};
+use ai_laoshi_core::chinese::HskLists;
+use ai_laoshi_core::{Card, Deck};
+use chrono::NaiveDate;
+use console::{style, Term};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Saves the `words` to the deck of the laoshi `dir`, or the new words of
+/// the `last_res` if no words.
+pub fn save_words(
+    dir: &Path,
+    words: &[String],
+    last_res: Option<&str>,
+) -> Result<()> {
+    let cedict = cedict()?;
+    let mut deck = Deck::load_from_dir(dir)?;
+    let today = today();
+
+    if words.is_empty() {
+        let Some(last_res) = last_res else {
+            println!("No answer yet. Usage: /save [word]...");
+            return Ok(());
+        };
+        let known = known_level()?;
+        let known = known
+            .as_ref()
+            .map(|(lists, level)| (lists.as_ref(), *level));
+        let added = deck.capture(last_res, &cedict, known, today);
+        deck.save()?;
+        match added.is_empty() {
+            true => println!("No new words in the last answer."),
+            false => println!("{}", added_msg(&added, &deck)),
+        }
+        return Ok(());
+    }
+
+    for word in words {
+        if deck.contains(word) {
+            println!("{} {word} is already in the deck", icon_check());
+            continue;
+        }
+        match Card::from_cedict(word, &cedict, today) {
+            Some(mut card) => {
+                if let Some(last_res) = last_res {
+                    card.set_example_from(last_res);
+                }
+                println!("{} {}", icon_check(), card_line(&card));
+                deck.add(card);
+            }
+            None => println!("{} {word} is not in the dictionary", icon_err()),
+        }
+    }
+    deck.save()?;
+
+    Ok(())
+}
+
+/// Saves the new words of the `res` if the [tutor] auto_save is on (errors
+/// are reported, never end the session).
+pub fn auto_save_words(dir: &Path, res: &str) {
+    if !auto_save() {
+        return;
+    }
+    let saved = cedict().and_then(|cedict| {
+        let mut deck = Deck::load_from_dir(dir)?;
+        let known = known_level()?;
+        let known = known
+            .as_ref()
+            .map(|(lists, level)| (lists.as_ref(), *level));
+        let added = deck.capture(res, &cedict, known, today());
+        deck.save()?;
+        Ok((added, deck))
+    });
+    match saved {
+        Ok((added, _)) if added.is_empty() => (),
+        Ok((added, deck)) => println!("{}", style(added_msg(&added, &deck)).dim()),
+        Err(e) => println!("{} {}", icon_err(), tutor_err_msg(&e)),
+    }
+}
+
+/// Reviews the due cards of the deck of the laoshi `dir`.
+pub fn review(dir: &Path) -> Result<()> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        return Err(Error::ReviewNeedsTerminal);
+    }
+    let mut deck = Deck::load_from_dir(dir)?;
+    let today = today();
+
+    let mut queue = deck.due_ids(today);
+    if queue.is_empty() {
+        let next = deck.cards().iter().map(|card| card.due).min();
+        match next {
+            None => println!("The deck is empty. Save words with /save <word>."),
+            Some(next) => println!(
+                "No words due ({} in the deck, next review on {next}).",
+                deck.len()
+            ),
+        }
+        return Ok(());
+    }
+
+    let total = queue.len();
+    let mut reviewed = 0;
+    let mut first_answered: HashSet<String> = HashSet::new();
+    println!("{total} words to review (q to stop).");
+    while !queue.is_empty() {
+        let id = queue.remove(0);
+        let Some(card) = deck.card(&id) else {
+            continue;
+        };
+
+        // -- Question
+        println!();
+        println!(
+            "{} {}",
+            style(format!("[{}/{total}]", reviewed + 1)).dim(),
+            style(&card.simplified).bold()
+        );
+        println!("{}", style("Press a key to show the answer").dim());
+        if is_quit(term.read_char()?) {
+            break;
+        }
+
+        // -- Answer
+        println!("{}", card_line(card));
+        println!(
+            "{}",
+            style("1 again · 2 hard · 3 good · 4 easy").fg(theme().accent)
+        );
+        let grade = loop {
+            match term.read_char()? {
+                c if is_quit(c) => break None,
+                // NOTE: The SM-2 grades (0-5), 3+ is a correct answer
+                '1' => break Some(1),
+                '2' => break Some(3),
+                '3' => break Some(4),
+                '4' => break Some(5),
+                _ => continue,
+            }
+        };
+        let Some(grade) = grade else {
+            break;
+        };
+
+        // NOTE: SM-2 on the first answer of the session only, the re-reviews
+        //       of a failed card just show it again until answered correctly
+        if first_answered.insert(id.clone()) {
+            if let Some(card) = deck.card_mut(&id) {
+                card.review(grade, today);
+            }
+            deck.save()?;
+        }
+        // NOTE: The failed cards come back at the end of the session
+        match grade < 3 {
+            true => queue.push(id),
+            false => reviewed += 1,
+        }
+    }
+
+    println!();
+    println!(
+        "{} {reviewed}/{total} reviewed, {} left for today",
+        icon_check(),
+        deck.due_ids(today).len()
+    );
+
+    Ok(())
+}
+
+/// Exports the deck of the laoshi `dir` as an Anki package or a TSV file
+/// (stdout if none).
+pub fn export_vocab(
+    dir: &Path,
+    anki: Option<&Path>,
+    tsv: Option<&Path>,
+) -> Result<()> {
+    let deck = Deck::load_from_dir(dir)?;
+    let file = match (anki, tsv) {
+        (Some(file), _) => {
+            deck.export_anki(file)?;
+            file
+        }
+        (None, Some(file)) => {
+            fs::write(file, deck.to_tsv())?;
+            file
+        }
+        (None, None) => {
+            print!("{}", deck.to_tsv());
+            return Ok(());
+        }
+    };
+    println!(
+        "{} {} words exported to {}",
+        icon_check(),
+        deck.len(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// Imports a TSV `file` into the deck of the laoshi `dir`.
+pub fn import_vocab(dir: &Path, file: &Path) -> Result<()> {
+    let content = fs::read_to_string(file)?;
+    let mut deck = Deck::load_from_dir(dir)?;
+    let summary = deck.import_tsv(&content, today());
+    deck.save()?;
+    println!(
+        "{} {} added, {} updated, {} skipped (no hanzi) - {} words",
+        icon_check(),
+        summary.added,
+        summary.updated,
+        summary.skipped,
+        deck.len()
+    );
+    Ok(())
+}
+
+// region:       -- Support
+
+fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// The HSK lists and the learner level of the known words, skipped by the
+/// deck capture (`None` if no [tutor] hsk_level).
+fn known_level() -> Result<Option<(Arc<HskLists>, u8)>> {
+    let (Some(level), _) = hsk_level() else {
+        return Ok(None);
+    };
+    Ok(Some((hsk_lists()?, level)))
+}
+
+fn is_quit(c: char) -> bool {
+    matches!(c, 'q' | 'Q' | '\u{1b}')
+}
+
+/// e.g., "学习 xuéxí  to learn; to study"
+fn card_line(card: &Card) -> String {
+    format!(
+        "{} {}  {}",
+        card.simplified,
+        style(&card.pinyin).fg(theme().accent),
+        card.definition
+    )
+}
+
+/// e.g., "+ 2 words saved to the deck: 学习, 世界 (12 words)"
+fn added_msg(added: &[String], deck: &Deck) -> String {
+    format!(
+        "+ {} word{} saved to the deck: {} ({} words)",
+        added.len(),
+        if added.len() == 1 { "" } else { "s" },
+        added.join(", "),
+        deck.len()
+    )
+}
+
+// endregion:    -- Support
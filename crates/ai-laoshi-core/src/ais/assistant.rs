@@ -17,22 +17,22 @@ use crate::{
     Error, Result,
 };
 use async_openai::{
-    config::OpenAIConfig,
+    config::{Config, OpenAIConfig},
     types::{
-        AssistantObject, AssistantToolsRetrieval, CreateAssistantFileRequest,
-        CreateAssistantRequest, CreateFileRequest, CreateRunRequest,
-        CreateThreadRequest, ModifyAssistantRequest, OpenAIFile, RunStatus,
+        AssistantObject, AssistantTools, AssistantToolsRetrieval,
+        CreateAssistantFileRequest, CreateAssistantRequest, CreateFileRequest,
+        CreateRunRequest, CreateThreadRequest, ModifyAssistantRequest, RunStatus,
         ThreadObject,
     },
     Assistants, Client,
 };
 use console::Term;
-use derive_more::{Deref, Display, From};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use serde_json::Value;
 use simple_fs::SPath;
 use std::{
     collections::{HashMap, HashSet},
-    thread::sleep,
+    path::Path,
     time::Duration,
 };
 
@@ -52,6 +52,24 @@ const POLLING_DURATION_MS: u64 = 500;
 pub struct CreateConfig {
     pub name: String,
     pub model: String,
+    pub description: Option<String>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// The tokens used by a run (or summed over several runs).
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub struct RunUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl RunUsage {
+    pub fn add(&mut self, other: &RunUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
 }
 
 // endregion:    -- Types
@@ -69,7 +87,9 @@ pub async fn create(
         .create(CreateAssistantRequest {
             model: config.model,
             name: Some(config.name),
-            tools: Some(vec![AssistantToolsRetrieval::default().into()]),
+            description: config.description,
+            tools: Some(default_tools()),
+            metadata: config.metadata.map(to_oa_metadata),
             ..Default::default()
         })
         .await?;
@@ -78,6 +98,10 @@ pub async fn create(
 }
 
 /// Create or load existing AssistantId
+///
+/// When loaded, the `config` fields that drifted from the remote assistant
+/// (e.g., `model` changed in laoshi.toml) are updated in place, without
+/// deleting the assistant or its files.
 // Q: Even with load_or_create_assistant(), we create multiple
 // Assistants in the OAI Platform. Something triggers a new create(),
 // even though my logs show it's LOADED an existing assistant...
@@ -87,23 +111,36 @@ pub async fn load_or_create_assistant(
     config: CreateConfig,
     recreate: bool,
 ) -> Result<AssistantId> {
-    let assistant_obj = first_by_name(oac, &config.name).await?;
-    let mut assistant_id = assistant_obj.map(|o| AssistantId::from(o.id));
-
-    // -- Delete assistant if recreate true & have assistant_id
-    if let (true, Some(assistant_id_ref)) = (recreate, assistant_id.as_ref()) {
-        delete(oac, assistant_id_ref).await?;
-        // Set assistant_id to None using Option<T>.take()
-        assistant_id.take();
+    let mut assistant_obj = first_by_name(oac, &config.name).await?;
+
+    // -- Delete assistant if recreate true & have assistant_obj
+    if let (true, Some(assistant_obj_ref)) = (recreate, assistant_obj.as_ref()) {
+        delete(oac, &assistant_obj_ref.id.clone().into()).await?;
+        // Set assistant_obj to None using Option<T>.take()
+        assistant_obj.take();
         println!("Assistant {} deleted", config.name);
     }
 
     // -- Load or create assistant if needed
-    // Could also use the let assistant_id = if let Some(assistant_id) = assistant_id {..} pattern
-    if let Some(assistant_id) = assistant_id {
+    // Could also use the let assistant_id = if let Some(assistant_obj) = assistant_obj {..} pattern
+    if let Some(assistant_obj) = assistant_obj {
         // We already have the Assistant
         println!("Assistant {} loaded.", config.name);
-        Ok(assistant_id)
+
+        // -- Reconcile the config drift (if any)
+        let (modify_request, drifted) = config_drift(&assistant_obj, &config);
+        if !drifted.is_empty() {
+            oac.assistants()
+                .update(&assistant_obj.id, modify_request)
+                .await?;
+            println!(
+                "Assistant {} updated ({}).",
+                config.name,
+                drifted.join(", ")
+            );
+        }
+
+        Ok(assistant_obj.id.into())
     } else {
         // We don't have an Assistant so need to create
         // Q: Why create assistant_name var?
@@ -115,6 +152,41 @@ pub async fn load_or_create_assistant(
     }
 }
 
+/// Returns the `ModifyAssistantRequest` for the `config` fields that differ
+/// from the remote `assistant_obj`, with the names of these fields.
+/// (empty names means no drift)
+///
+/// NOTE: `description` and `metadata` are only managed when set in the config.
+pub fn config_drift(
+    assistant_obj: &AssistantObject,
+    config: &CreateConfig,
+) -> (ModifyAssistantRequest, Vec<&'static str>) {
+    let mut modify_request = ModifyAssistantRequest::default();
+    let mut drifted = Vec::new();
+
+    if assistant_obj.model != config.model {
+        modify_request.model = Some(config.model.clone());
+        drifted.push("model");
+    }
+
+    if let Some(description) = config.description.as_ref() {
+        if assistant_obj.description.as_ref() != Some(description) {
+            modify_request.description = Some(description.clone());
+            drifted.push("description");
+        }
+    }
+
+    if let Some(metadata) = config.metadata.as_ref() {
+        let metadata = to_oa_metadata(metadata.clone());
+        if assistant_obj.metadata.as_ref() != Some(&metadata) {
+            modify_request.metadata = Some(metadata);
+            drifted.push("metadata");
+        }
+    }
+
+    (modify_request, drifted)
+}
+
 pub async fn first_by_name(
     oac: &Client<OpenAIConfig>,
     name: &str,
@@ -168,7 +240,7 @@ pub async fn delete(
 
     // -- Delete ORG files since our Assistant may have files associated with it
     // NOTE: TIP! There's a handy HashMap.into_values()
-    for file_id in get_files_hashmap(&oac, assistant_id).await?.into_values() {
+    for file_id in get_files_hashmap(oac, assistant_id).await?.into_values() {
         // NOTE: !! The file might already be deleted, so we don't
         // have it stop/end with Err() by using '?' operator.
         let del_res = oa_org_files_obj.delete(&file_id).await;
@@ -190,6 +262,37 @@ pub async fn delete(
 
 // endregion:    -- Assistant CRUD
 
+// region:       -- Support
+
+/// The tools our assistants are created with (retrieval for the bundles).
+pub fn default_tools() -> Vec<AssistantTools> {
+    vec![AssistantToolsRetrieval::default().into()]
+}
+
+/// The tool names for display/compare, e.g., `["retrieval"]`
+pub fn tool_names(tools: &[AssistantTools]) -> Vec<String> {
+    tools
+        .iter()
+        .map(|tool| match tool {
+            AssistantTools::Code(tool) => tool.r#type.clone(),
+            AssistantTools::Retrieval(tool) => tool.r#type.clone(),
+            AssistantTools::Function(tool) => {
+                format!("{}:{}", tool.r#type, tool.function.name)
+            }
+        })
+        .collect()
+}
+
+/// OpenAI metadata values are JSON, but we only manage string values.
+fn to_oa_metadata(metadata: HashMap<String, String>) -> HashMap<String, Value> {
+    metadata
+        .into_iter()
+        .map(|(k, v)| (k, Value::String(v)))
+        .collect()
+}
+
+// endregion:    -- Support
+
 // region:       -- Threads that Assistants can interact with
 pub async fn create_thread(oac: &Client<OpenAIConfig>) -> Result<ThreadId> {
     let oa_threads_obj = oac.threads();
@@ -212,6 +315,15 @@ pub async fn get_thread(
     Ok(thread_obj)
 }
 
+/// Deletes a thread (e.g., the one-off threads of a quiz).
+///
+/// NOTE: Errors are only logged, since the thread might already be deleted.
+pub async fn delete_thread(oac: &Client<OpenAIConfig>, thread_id: &ThreadId) {
+    if let Err(err) = oac.threads().delete(thread_id).await {
+        eprintln!("X Can't delete thread '{}'\n    cause: {}", thread_id, err);
+    }
+}
+
 /// Send message to Thread/Conversation
 // NOTE: Could extend this to also upload files.
 // NOTE: We're keeping the messaging simple, but our
@@ -222,12 +334,19 @@ pub async fn run_thread_msg(
     assistant_id: &AssistantId,
     thread_id: &ThreadId,
     msg: &str,
-) -> Result<String> {
-    // -- Create OpenAI Message
-    let message_request = message::create_user_message(msg);
+    file_ids: &[FileId],
+    additional_instructions: Option<&str>,
+    show_progress: bool,
+) -> Result<(String, Option<RunUsage>)> {
+    // -- Create OpenAI Message (with the message-level files, if any)
+    let mut message_request = message::create_user_message(msg);
+    if !file_ids.is_empty() {
+        message_request.file_ids =
+            Some(file_ids.iter().map(|id| id.to_string()).collect());
+    }
 
     // -- Attach message to thread
-    let message_obj = oac
+    let _message_obj = oac
         .threads()
         .messages(thread_id)
         .create(message_request)
@@ -235,8 +354,11 @@ pub async fn run_thread_msg(
 
     // -- Create a run for the thread
     // NOTE: This is where you can configure model, ixs, tools, metadata
+    // NOTE: The additional instructions are appended to the assistant ones,
+    // for this run only (e.g., the learner profile)
     let run_request = CreateRunRequest {
         assistant_id: assistant_id.to_string(),
+        additional_instructions: additional_instructions.map(str::to_string),
         ..Default::default()
     };
     // NOTE: This sends the request to the API
@@ -245,23 +367,34 @@ pub async fn run_thread_msg(
     // -- Loop through RunObject until you get a result and print execution
     // NOTE: This is where the 'console' crate comes in
     // Add some print/log statuses. However, the loop will flush it
+    // NOTE: `show_progress` is false for full-screen UIs (would mess the screen)
     let term = Term::stdout();
+    let progress = |txt: &str| match show_progress {
+        true => term.write_str(txt),
+        false => Ok(()),
+    };
     loop {
         // NOTE: Need to add new custom Error::IO enum variant for std errors
-        term.write_str(">")?;
+        progress(">")?;
         let run_obj = oac.threads().runs(thread_id).retrieve(&run_obj.id).await?;
-        term.write_str("< ")?;
+        progress("< ")?;
 
         match run_obj.status {
             // NOTE: This returns only out of the match (not the whole function!)
             RunStatus::Queued | RunStatus::InProgress => (), // Continue looping
             RunStatus::Completed => {
-                term.write_str("\n")?;
+                progress("\n")?;
+                // NOTE: The usage is informational, a failure does not fail the run
+                let usage = get_run_usage(oac, thread_id, &run_obj.id)
+                    .await
+                    .ok()
+                    .flatten();
                 // NOTE: This 'return' returns out of the whole function (not just the match!)
-                return get_first_thread_message_content(oac, thread_id).await;
+                let text = get_first_thread_message_content(oac, thread_id).await?;
+                return Ok((text, usage));
             }
             other => {
-                term.write_str("\n")?;
+                progress("\n")?;
                 return Err(Error::RunError(other));
                 // return Err(format!("ERROR WHILE RUN: {:?}", other).into());
             }
@@ -271,6 +404,33 @@ pub async fn run_thread_msg(
     }
 }
 
+/// Returns the token usage of the run (`None` if not reported yet).
+// WARN: async-openai 0.18 `RunObject` does not have the `usage` property,
+// so we get the run JSON directly (same url and headers as the client).
+pub async fn get_run_usage(
+    oac: &Client<OpenAIConfig>,
+    thread_id: &ThreadId,
+    run_id: &str,
+) -> Result<Option<RunUsage>> {
+    #[derive(Deserialize)]
+    struct RunJson {
+        usage: Option<RunUsage>,
+    }
+
+    let config = oac.config();
+    let url = config.url(&format!("/threads/{thread_id}/runs/{run_id}"));
+    let run: RunJson = reqwest::Client::new()
+        .get(url)
+        .headers(config.headers())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(run.usage)
+}
+
 // NOTE: Once we get an Ok() from the run_thread_msg(), we want
 // the latest message of the thread.
 pub async fn get_first_thread_message_content(
@@ -366,7 +526,7 @@ pub async fn upload_file_by_name(
     // Q: Get the HashMap of Assistant files and then
     // look for a match on file_name?
     // U: Kinda... Need to use if let Some(file_id) or if let Err(err) more...
-    let mut assistant_files_hm = get_files_hashmap(&oac, assistant_id).await?;
+    let mut assistant_files_hm = get_files_hashmap(oac, assistant_id).await?;
     // Q: Why remove() instead of just get()?
     // A: Because we need an owned Option<FileId> and don't need the HM afterwards.
     // If we use get(), it gives a ref Option<&FileId> and then we'll need
@@ -383,21 +543,7 @@ pub async fn upload_file_by_name(
 
     // -- If file already exists (old) and force is true, delete file & assistant file association
     if let Some(file_id) = file_id {
-        // -- Delete the org file
-        let oa_org_files_obj = oac.files();
-        if let Err(err) = oa_org_files_obj.delete(&file_id).await {
-            eprintln!("X Can't delete file '{}'\n    cause: {}", file_name, err);
-        }
-
-        // -- Delete the Assistant file association
-        let oa_assistants_obj = oac.assistants();
-        let oa_assistant_files_obj = oa_assistants_obj.files(assistant_id);
-        if let Err(err) = oa_assistant_files_obj.delete(&file_id).await {
-            eprintln!(
-                "X Can't delete assistant file '{}'\n    cause: {}",
-                file_name, err
-            )
-        }
+        delete_file(oac, assistant_id, &file_id, file_name).await;
     }
 
     // -- Upload file to OpenAI org account
@@ -438,4 +584,60 @@ pub async fn upload_file_by_name(
     Ok((assistant_file_obj.id.into(), true))
 }
 
+/// Uploads a file to the org account only (not attached to the assistant),
+/// to be attached to a single message (see `run_thread_msg`).
+pub async fn upload_message_file(
+    oac: &Client<OpenAIConfig>,
+    file: &Path,
+) -> Result<FileId> {
+    let oa_org_file_obj = oac
+        .files()
+        .create(CreateFileRequest {
+            file: file.into(),
+            purpose: "assistants".into(),
+        })
+        .await?;
+
+    Ok(oa_org_file_obj.id.into())
+}
+
+/// Deletes a message file from the org account.
+///
+/// NOTE: Errors are only logged, since the file might already be deleted.
+pub async fn delete_message_file(
+    oac: &Client<OpenAIConfig>,
+    file_id: &FileId,
+    file_name: &str,
+) {
+    if let Err(err) = oac.files().delete(file_id).await {
+        eprintln!("X Can't delete file '{}'\n    cause: {}", file_name, err);
+    }
+}
+
+/// Deletes the file from the org account and detaches it from the assistant.
+///
+/// NOTE: Errors are only logged, since the file might already be deleted.
+pub async fn delete_file(
+    oac: &Client<OpenAIConfig>,
+    assistant_id: &AssistantId,
+    file_id: &FileId,
+    file_name: &str,
+) {
+    // -- Delete the org file
+    let oa_org_files_obj = oac.files();
+    if let Err(err) = oa_org_files_obj.delete(file_id).await {
+        eprintln!("X Can't delete file '{}'\n    cause: {}", file_name, err);
+    }
+
+    // -- Delete the Assistant file association
+    let oa_assistants_obj = oac.assistants();
+    let oa_assistant_files_obj = oa_assistants_obj.files(assistant_id);
+    if let Err(err) = oa_assistant_files_obj.delete(file_id).await {
+        eprintln!(
+            "X Can't delete assistant file '{}'\n    cause: {}",
+            file_name, err
+        )
+    }
+}
+
 // endregion:    -- Files
@@ -23,13 +23,24 @@ const ENV_OPENAI_API_KEY: &str = "OPENAI_API_KEY";
 
 // pub type OaClient = Client<OpenAIConfig>;
 
-pub fn new_openai_client() -> Result<Client<OpenAIConfig>> {
-    if std::env::var(ENV_OPENAI_API_KEY).is_ok() {
-        Ok(Client::new())
-    } else {
-        println!("No {ENV_OPENAI_API_KEY} env variable found. Please configure.");
-        Err(Error::NoOpenAIApiKeyInEnv)
+/// New client with the given `api_key` (or the OPENAI_API_KEY env var if `None`)
+/// and optional `api_base` (e.g., a proxy or compatible endpoint).
+pub fn new_openai_client(
+    api_key: Option<String>,
+    api_base: Option<&str>,
+) -> Result<Client<OpenAIConfig>> {
+    let Some(api_key) = api_key.or_else(|| std::env::var(ENV_OPENAI_API_KEY).ok())
+    else {
+        println!("No api key or {ENV_OPENAI_API_KEY} env variable found. Please configure.");
+        return Err(Error::NoOpenAIApiKeyInEnv);
+    };
+
+    let mut oa_config = OpenAIConfig::new().with_api_key(api_key);
+    if let Some(api_base) = api_base {
+        oa_config = oa_config.with_api_base(api_base);
     }
+
+    Ok(Client::with_config(oa_config))
 }
 
 // endregion:    -- Create Async OpenAI Client
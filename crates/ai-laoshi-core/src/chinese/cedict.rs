@@ -0,0 +1,144 @@
+// NOTE: A CC-CEDICT dictionary (https://cc-cedict.org/), loaded from a local
+// file kept in the laoshi dir (no network). One entry per line:
+// `Traditional Simplified [pin1 yin1] /definition 1/definition 2/`
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// The default CC-CEDICT file name (in the laoshi dir).
+pub const CEDICT_FILE: &str = "cedict_ts.u8";
+
+#[derive(Debug, Clone)]
+pub struct CedictEntry {
+    pub traditional: String,
+    pub simplified: String,
+    /// Numbered pinyin, space separated (e.g., "ni3 hao3")
+    pub pinyin: String,
+    pub definitions: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Cedict {
+    entries: Vec<CedictEntry>,
+    /// The entry indexes by simplified and traditional forms
+    by_hanzi: HashMap<String, Vec<usize>>,
+    /// The longest word, in chars (for the segmentation)
+    max_word_chars: usize,
+}
+
+impl Cedict {
+    /// Loads the CC-CEDICT `file` (fails if missing).
+    pub fn load(file: impl AsRef<Path>) -> Result<Self> {
+        let file = file.as_ref();
+        if !file.is_file() {
+            return Err(Error::CedictNotFound(file.to_string_lossy().to_string()));
+        }
+        let content = fs::read_to_string(file)?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Parses the CC-CEDICT `content` (comments and invalid lines skipped).
+    pub fn parse(content: &str) -> Self {
+        let mut cedict = Cedict::default();
+        for entry in content.lines().filter_map(parse_line) {
+            let idx = cedict.entries.len();
+            cedict.max_word_chars =
+                cedict.max_word_chars.max(entry.simplified.chars().count());
+            cedict
+                .by_hanzi
+                .entry(entry.simplified.clone())
+                .or_default()
+                .push(idx);
+            if entry.traditional != entry.simplified {
+                cedict
+                    .by_hanzi
+                    .entry(entry.traditional.clone())
+                    .or_default()
+                    .push(idx);
+            }
+            cedict.entries.push(entry);
+        }
+        cedict
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[CedictEntry] {
+        &self.entries
+    }
+
+    /// The entries of the `hanzi` word (simplified or traditional).
+    pub fn lookup(&self, hanzi: &str) -> Vec<&CedictEntry> {
+        self.by_hanzi
+            .get(hanzi)
+            .map(|idxs| idxs.iter().map(|idx| &self.entries[*idx]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn contains(&self, hanzi: &str) -> bool {
+        self.by_hanzi.contains_key(hanzi)
+    }
+
+    pub fn max_word_chars(&self) -> usize {
+        self.max_word_chars
+    }
+
+    /// The most common entry of the `hanzi` word.
+    // NOTE: CC-CEDICT has no frequencies, so we skip the proper nouns
+    // (capitalized pinyin) and the variant/surname-only entries when we can.
+    pub fn best_entry(&self, hanzi: &str) -> Option<&CedictEntry> {
+        self.lookup(hanzi)
+            .into_iter()
+            .min_by_key(|entry| reading_rank(entry))
+    }
+
+    /// The most common reading of the `hanzi` word (numbered pinyin).
+    pub fn reading(&self, hanzi: &str) -> Option<&str> {
+        self.best_entry(hanzi).map(|entry| entry.pinyin.as_str())
+    }
+}
+
+// region:       -- Support
+
+/// e.g., `中國 中国 [Zhong1 guo2] /China/`
+fn parse_line(line: &str) -> Option<CedictEntry> {
+    if line.starts_with('#') {
+        return None;
+    }
+    let (words, rest) = line.split_once(" [")?;
+    let (traditional, simplified) = words.split_once(' ')?;
+    let (pinyin, definitions) = rest.split_once("] /")?;
+    let definitions = definitions
+        .trim_end()
+        .trim_end_matches('/')
+        .split('/')
+        .map(str::to_string)
+        .collect();
+
+    Some(CedictEntry {
+        traditional: traditional.to_string(),
+        simplified: simplified.to_string(),
+        pinyin: pinyin.to_string(),
+        definitions,
+    })
+}
+
+/// Lower is more common.
+pub(super) fn reading_rank(entry: &CedictEntry) -> u8 {
+    let is_proper_noun = entry.pinyin.starts_with(|c: char| c.is_uppercase());
+    let is_variant = entry.definitions.iter().all(|def| {
+        def.starts_with("variant of")
+            || def.starts_with("old variant of")
+            || def.starts_with("surname ")
+    });
+    is_proper_noun as u8 + is_variant as u8
+}
+
+// endregion:    -- Support
@@ -0,0 +1,223 @@
+// NOTE: The offline Simplified <-> Traditional conversion of a text, by
+// forward maximum matching on conversion tables (longest phrase first), so
+// the ambiguous chars are converted by their phrase (e.g., 头发 -> 頭髮 but
+// 发现 -> 發現).
+//
+// The tables come from the CC-CEDICT word pairs (the words of 2+ chars, and
+// each single char to its most common form in these words), optionally
+// overridden by OpenCC text tables kept in the laoshi dir (no network):
+//
+// laoshi/opencc/STPhrases.txt, STCharacters.txt (to traditional)
+// laoshi/opencc/TSPhrases.txt, TSCharacters.txt (to simplified)
+//
+// One entry per line, `from<TAB>to [other to...]` (the first `to` is used).
+use crate::chinese::cedict::reading_rank;
+use crate::chinese::{Cedict, Script};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// The default OpenCC tables dir (in the laoshi dir).
+pub const OPENCC_DIR: &str = "opencc";
+
+/// The OpenCC table files, and the script they convert to.
+const OPENCC_TABLES: &[(&str, Script)] = &[
+    ("STCharacters.txt", Script::Traditional),
+    ("STPhrases.txt", Script::Traditional),
+    ("TSCharacters.txt", Script::Simplified),
+    ("TSPhrases.txt", Script::Simplified),
+];
+
+#[derive(Debug, Default)]
+pub struct ScriptConverter {
+    to_traditional: Table,
+    to_simplified: Table,
+}
+
+#[derive(Debug, Default)]
+struct Table {
+    phrases: HashMap<String, String>,
+    /// The longest phrase, in chars
+    max_chars: usize,
+}
+
+impl ScriptConverter {
+    /// The conversion tables of the CC-CEDICT words.
+    pub fn from_cedict(cedict: &Cedict) -> Self {
+        let mut converter = ScriptConverter::default();
+
+        // -- The words (the most common entry first)
+        let mut entries: Vec<_> = cedict.entries().iter().collect();
+        entries.sort_by_key(|entry| reading_rank(entry));
+        // The char pairs of the words, and their counts (the single chars)
+        let mut char_pairs: HashMap<(char, char), usize> = HashMap::new();
+        for entry in entries {
+            let (simplified, traditional) = (&entry.simplified, &entry.traditional);
+            if simplified.chars().count() != traditional.chars().count() {
+                continue;
+            }
+            for pair in simplified.chars().zip(traditional.chars()) {
+                *char_pairs.entry(pair).or_default() += 1;
+            }
+            if simplified.chars().count() > 1 {
+                converter.to_traditional.add_new(simplified, traditional);
+                converter.to_simplified.add_new(traditional, simplified);
+            }
+        }
+
+        // -- The single chars (the most frequent pair)
+        let mut char_pairs: Vec<((char, char), usize)> =
+            char_pairs.into_iter().collect();
+        // NOTE: Sorted so the ties are stable (the lower code point first)
+        char_pairs.sort_by_key(|(pair, count)| (std::cmp::Reverse(*count), *pair));
+        for ((simplified, traditional), _) in char_pairs {
+            converter
+                .to_traditional
+                .add_new(&simplified.to_string(), &traditional.to_string());
+            converter
+                .to_simplified
+                .add_new(&traditional.to_string(), &simplified.to_string());
+        }
+
+        converter
+    }
+
+    /// Adds (overrides) the OpenCC tables of the `dir`, if any. Returns the
+    /// number of tables found.
+    pub fn add_opencc_tables(&mut self, dir: impl AsRef<Path>) -> usize {
+        let mut found = 0;
+        for (file_name, script) in OPENCC_TABLES {
+            let Ok(content) = fs::read_to_string(dir.as_ref().join(file_name))
+            else {
+                continue;
+            };
+            self.add_table(*script, &content);
+            found += 1;
+        }
+        found
+    }
+
+    /// Adds (overrides) the `from<TAB>to` lines of a table `content`
+    /// converting to the `script`.
+    pub fn add_table(&mut self, script: Script, content: &str) {
+        let table = match script {
+            Script::Traditional => &mut self.to_traditional,
+            Script::Simplified => &mut self.to_simplified,
+        };
+        let pairs = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('\t'))
+            .filter_map(|(from, to)| {
+                Some((from.trim(), to.split_whitespace().next()?))
+            });
+        for (from, to) in pairs {
+            table.add(from, to);
+        }
+    }
+
+    /// The `text` in the `script` (the unknown chars as is).
+    pub fn convert(&self, text: &str, script: Script) -> String {
+        let table = match script {
+            Script::Traditional => &self.to_traditional,
+            Script::Simplified => &self.to_simplified,
+        };
+
+        let mut converted = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(first) = rest.chars().next() {
+            // NOTE: The char boundaries, up to the longest phrase
+            let ends: Vec<usize> = rest
+                .char_indices()
+                .skip(1)
+                .map(|(idx, _)| idx)
+                .chain(std::iter::once(rest.len()))
+                .take(table.max_chars.max(1))
+                .collect();
+            let phrase = ends
+                .iter()
+                .rev()
+                .find_map(|end| Some((*end, table.phrases.get(&rest[..*end])?)));
+            match phrase {
+                Some((end, to)) => {
+                    converted.push_str(to);
+                    rest = &rest[end..];
+                }
+                None => {
+                    converted.push(first);
+                    rest = &rest[first.len_utf8()..];
+                }
+            }
+        }
+        converted
+    }
+}
+
+impl Table {
+    fn add(&mut self, from: &str, to: &str) {
+        self.max_chars = self.max_chars.max(from.chars().count());
+        self.phrases.insert(from.to_string(), to.to_string());
+    }
+
+    /// Adds the phrase if not there yet.
+    fn add_new(&mut self, from: &str, to: &str) {
+        if !self.phrases.contains_key(from) {
+            self.add(from, to);
+        }
+    }
+}
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    const FX_CEDICT: &str = "\
+學習 学习 [xue2 xi2] /to learn/
+頭髮 头发 [tou2 fa5] /hair/
+發 发 [fa1] /to send out/
+們 们 [men5] /plural marker/
+我 我 [wo3] /I/
+";
+
+    #[test]
+    fn test_convert_both_ways() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_converter = ScriptConverter::from_cedict(&Cedict::parse(FX_CEDICT));
+
+        // -- Exec & Check
+        assert_eq!(
+            fx_converter.convert("我们学习。OK", Script::Traditional),
+            "我們學習。OK"
+        );
+        assert_eq!(
+            fx_converter.convert("我們學習。OK", Script::Simplified),
+            "我们学习。OK"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_phrases_win() -> Result<()> {
+        // -- Setup & Fixtures
+        let mut fx_converter =
+            ScriptConverter::from_cedict(&Cedict::parse(FX_CEDICT));
+
+        // -- Exec & Check
+        // The phrase (头发 -> 頭髮), not the char pairs (发 -> 發)
+        assert_eq!(fx_converter.convert("头发", Script::Traditional), "頭髮");
+        assert_eq!(fx_converter.convert("发", Script::Traditional), "發");
+
+        // An added table overrides the CC-CEDICT pairs
+        fx_converter.add_table(Script::Traditional, "# comment\n发\t髮 發\n");
+        assert_eq!(fx_converter.convert("发", Script::Traditional), "髮");
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
@@ -0,0 +1,296 @@
+// NOTE: The dictionary lookup of the local CC-CEDICT (no API call), by:
+// - hanzi: the word (simplified or traditional), then the longer words
+//   starting with it,
+// - pinyin: with or without tones (e.g., "nihao", "ni3 hao3", "nǐhǎo"),
+// - English: the reverse lookup of the definitions (e.g., "hello").
+// A query can match as pinyin and English both (e.g., "ma").
+use crate::chinese::cedict::reading_rank;
+use crate::chinese::{is_hanzi, Cedict, CedictEntry};
+
+/// How an entry matched the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DictMatch {
+    Hanzi,
+    Pinyin,
+    English,
+}
+
+#[derive(Debug, Clone)]
+pub struct DictHit<'a> {
+    pub matched: DictMatch,
+    pub entry: &'a CedictEntry,
+}
+
+impl Cedict {
+    /// The entries matching the `query` (by match kind, most relevant first).
+    pub fn search(&self, query: &str) -> Vec<DictHit<'_>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        if query.chars().any(is_hanzi) {
+            return self.search_hanzi(query);
+        }
+
+        let mut hits = Vec::new();
+        if is_pinyin_like(query) {
+            hits.extend(self.search_pinyin(query));
+        }
+        hits.extend(self.search_english(query));
+        hits
+    }
+
+    fn search_hanzi(&self, query: &str) -> Vec<DictHit<'_>> {
+        let mut entries = self.lookup(query);
+        entries.sort_by_key(|entry| reading_rank(entry));
+
+        // -- The longer words starting with it (e.g., 学 -> 学生, 学习)
+        let mut longer: Vec<&CedictEntry> = self
+            .entries()
+            .iter()
+            .filter(|entry| {
+                (entry.simplified.starts_with(query)
+                    || entry.traditional.starts_with(query))
+                    && entry.simplified != query
+                    && entry.traditional != query
+            })
+            .collect();
+        longer.sort_by_key(|entry| {
+            (entry.simplified.chars().count(), reading_rank(entry))
+        });
+
+        entries
+            .into_iter()
+            .chain(longer)
+            .map(|entry| DictHit {
+                matched: DictMatch::Hanzi,
+                entry,
+            })
+            .collect()
+    }
+
+    fn search_pinyin(&self, query: &str) -> Vec<DictHit<'_>> {
+        let (key, tones) = (toneless(query), tone_digits(query));
+        let has_tones = query.chars().any(|c| {
+            c.is_ascii_digit() || base_vowel(c).is_some_and(|(_, t)| t.is_some())
+        });
+        let mut entries: Vec<&CedictEntry> = self
+            .entries()
+            .iter()
+            .filter(|entry| toneless(&entry.pinyin) == key)
+            // NOTE: The tones are checked only if the query has some
+            .filter(|entry| !has_tones || tone_digits(&entry.pinyin) == tones)
+            .collect();
+        entries.sort_by_key(|entry| reading_rank(entry));
+        entries
+            .into_iter()
+            .map(|entry| DictHit {
+                matched: DictMatch::Pinyin,
+                entry,
+            })
+            .collect()
+    }
+
+    fn search_english(&self, query: &str) -> Vec<DictHit<'_>> {
+        let query = query.to_lowercase();
+        let mut entries: Vec<(u8, &CedictEntry)> = self
+            .entries()
+            .iter()
+            .filter_map(|entry| {
+                let rank = entry
+                    .definitions
+                    .iter()
+                    .filter_map(|def| english_rank(&def.to_lowercase(), &query))
+                    .min()?;
+                Some((rank, entry))
+            })
+            .collect();
+        entries.sort_by_key(|(rank, entry)| {
+            (*rank, reading_rank(entry), entry.simplified.chars().count())
+        });
+        entries
+            .into_iter()
+            .map(|(_, entry)| DictHit {
+                matched: DictMatch::English,
+                entry,
+            })
+            .collect()
+    }
+}
+
+// region:       -- Support
+
+/// `true` if the `query` can be pinyin (letters, tone numbers or marks).
+fn is_pinyin_like(query: &str) -> bool {
+    query.chars().all(|c| {
+        c.is_ascii_alphanumeric()
+            || matches!(c, ' ' | '\'' | ':')
+            || base_vowel(c).is_some()
+    })
+}
+
+/// e.g., "Ni3 hao3" -> "nihao", "nǐhǎo" -> "nihao", "lu:4" -> "lv"
+fn toneless(pinyin: &str) -> String {
+    pinyin
+        .replace("u:", "v")
+        .replace("U:", "v")
+        .chars()
+        .filter(|c| !c.is_ascii_digit() && !matches!(c, ' ' | '\'' | '-'))
+        .map(|c| base_vowel(c).map(|(base, _)| base).unwrap_or(c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The tones 1-4 in order (the neutral tone left out), e.g.,
+/// "ni3 hao3" -> "33", "nǐhǎo" -> "33", "ma5" -> "".
+fn tone_digits(pinyin: &str) -> String {
+    pinyin
+        .chars()
+        .filter_map(|c| match c {
+            '1'..='4' => Some(c),
+            c => base_vowel(c).and_then(|(_, tone)| tone),
+        })
+        .collect()
+}
+
+/// The base vowel and tone of a pinyin vowel with a mark (or ü).
+fn base_vowel(c: char) -> Option<(char, Option<char>)> {
+    let (base, tone) = match c {
+        'ā' | 'Ā' => ('a', '1'),
+        'á' | 'Á' => ('a', '2'),
+        'ǎ' | 'Ǎ' => ('a', '3'),
+        'à' | 'À' => ('a', '4'),
+        'ē' | 'Ē' => ('e', '1'),
+        'é' | 'É' => ('e', '2'),
+        'ě' | 'Ě' => ('e', '3'),
+        'è' | 'È' => ('e', '4'),
+        'ī' | 'Ī' => ('i', '1'),
+        'í' | 'Í' => ('i', '2'),
+        'ǐ' | 'Ǐ' => ('i', '3'),
+        'ì' | 'Ì' => ('i', '4'),
+        'ō' | 'Ō' => ('o', '1'),
+        'ó' | 'Ó' => ('o', '2'),
+        'ǒ' | 'Ǒ' => ('o', '3'),
+        'ò' | 'Ò' => ('o', '4'),
+        'ū' | 'Ū' => ('u', '1'),
+        'ú' | 'Ú' => ('u', '2'),
+        'ǔ' | 'Ǔ' => ('u', '3'),
+        'ù' | 'Ù' => ('u', '4'),
+        'ǖ' | 'Ǖ' => ('v', '1'),
+        'ǘ' | 'Ǘ' => ('v', '2'),
+        'ǚ' | 'Ǚ' => ('v', '3'),
+        'ǜ' | 'Ǜ' => ('v', '4'),
+        'ü' | 'Ü' => return Some(('v', None)),
+        _ => return None,
+    };
+    Some((base, Some(tone)))
+}
+
+/// Lower is more relevant, `None` if the (lowercase) `def` has no `query`
+/// word, e.g., for "eat": "to eat" (0), "eat (breakfast)" (1),
+/// "to eat a meal" (2).
+fn english_rank(def: &str, query: &str) -> Option<u8> {
+    // NOTE: The notes in brackets are left out (e.g., "to eat (food)")
+    let main = def.split(" (").next().unwrap_or(def).trim();
+    let main = main.strip_prefix("to ").unwrap_or(main);
+    if main == query {
+        return Some(0);
+    }
+    let words: Vec<&str> = def
+        .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+    let position = words
+        .windows(query_words.len().max(1))
+        .position(|window| window == query_words.as_slice())?;
+    Some(if position == 0 { 1 } else { 2 })
+}
+
+// endregion:    -- Support
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    const FX_CEDICT: &str = "\
+學 学 [xue2] /to learn/
+學生 学生 [xue2 sheng5] /student/
+學習 学习 [xue2 xi2] /to learn/to study/
+你好 你好 [ni3 hao3] /hello/hi/
+媽 妈 [ma1] /mum/
+嗎 吗 [ma5] /(question particle)/
+馬 马 [ma3] /horse/
+";
+
+    #[test]
+    fn test_search_hanzi_then_longer_words() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_dict = Cedict::parse(FX_CEDICT);
+
+        // -- Exec
+        let hits = fx_dict.search("学");
+
+        // -- Check
+        let words: Vec<&str> = hits
+            .iter()
+            .map(|hit| hit.entry.simplified.as_str())
+            .collect();
+        assert_eq!(words, ["学", "学生", "学习"]);
+        assert!(hits.iter().all(|hit| hit.matched == DictMatch::Hanzi));
+        // The traditional form finds the same word
+        assert_eq!(fx_dict.search("學習")[0].entry.simplified, "学习");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_pinyin_with_and_without_tones() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_dict = Cedict::parse(FX_CEDICT);
+        let words = |query: &str| -> Vec<String> {
+            fx_dict
+                .search(query)
+                .into_iter()
+                .filter(|hit| hit.matched == DictMatch::Pinyin)
+                .map(|hit| hit.entry.simplified.clone())
+                .collect()
+        };
+
+        // -- Exec & Check
+        assert_eq!(words("nihao"), ["你好"]);
+        assert_eq!(words("ni3 hao3"), ["你好"]);
+        assert_eq!(words("nǐhǎo"), ["你好"]);
+        assert!(words("ni2hao3").is_empty());
+        assert_eq!(words("ma").len(), 3);
+        assert_eq!(words("ma3"), ["马"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_english_ranked() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_dict = Cedict::parse(FX_CEDICT);
+
+        // -- Exec
+        let hits = fx_dict.search("learn");
+
+        // -- Check
+        let words: Vec<&str> = hits
+            .iter()
+            .map(|hit| hit.entry.simplified.as_str())
+            .collect();
+        assert_eq!(words, ["学", "学习"]);
+        assert!(hits.iter().all(|hit| hit.matched == DictMatch::English));
+        assert!(fx_dict.search("  ").is_empty());
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
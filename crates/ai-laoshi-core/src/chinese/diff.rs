@@ -0,0 +1,117 @@
+// NOTE: The character-level diff of a learner sentence and its correction
+// (longest common subsequence on the chars, fine for sentences). The
+// consecutive chars of the same kind are grouped, e.g.,
+// "我有三个书" -> "我有三本书": Same("我有三"), Removed("个"), Added("本"),
+// Same("书").
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diff {
+    Same(String),
+    /// Only in the original text
+    Removed(String),
+    /// Only in the corrected text
+    Added(String),
+}
+
+/// The diff of the `original` text to the `corrected` one (the removed chars
+/// before the added ones at the same position).
+pub fn char_diff(original: &str, corrected: &str) -> Vec<Diff> {
+    let from: Vec<char> = original.chars().collect();
+    let to: Vec<char> = corrected.chars().collect();
+
+    // -- The LCS lengths of the suffixes
+    let mut lcs = vec![vec![0usize; to.len() + 1]; from.len() + 1];
+    for i in (0..from.len()).rev() {
+        for j in (0..to.len()).rev() {
+            lcs[i][j] = match from[i] == to[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    // -- Walk the table (grouped by kind)
+    let mut diffs: Vec<Diff> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < from.len() || j < to.len() {
+        if i < from.len() && j < to.len() && from[i] == to[j] {
+            push(&mut diffs, Diff::Same(from[i].to_string()));
+            (i, j) = (i + 1, j + 1);
+        } else if i < from.len() && (j == to.len() || lcs[i + 1][j] >= lcs[i][j + 1])
+        {
+            push(&mut diffs, Diff::Removed(from[i].to_string()));
+            i += 1;
+        } else {
+            push(&mut diffs, Diff::Added(to[j].to_string()));
+            j += 1;
+        }
+    }
+    diffs
+}
+
+// region:       -- Support
+
+/// Pushes the `diff`, appended to the last one if of the same kind.
+fn push(diffs: &mut Vec<Diff>, diff: Diff) {
+    match (diffs.last_mut(), diff) {
+        (Some(Diff::Same(last)), Diff::Same(text))
+        | (Some(Diff::Removed(last)), Diff::Removed(text))
+        | (Some(Diff::Added(last)), Diff::Added(text)) => last.push_str(&text),
+        (_, diff) => diffs.push(diff),
+    }
+}
+
+// endregion:    -- Support
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    #[test]
+    fn test_char_diff_replaced() -> Result<()> {
+        // -- Exec
+        let diffs = char_diff("我有三个书", "我有三本书");
+
+        // -- Check
+        assert_eq!(
+            diffs,
+            vec![
+                Diff::Same("我有三".to_string()),
+                Diff::Removed("个".to_string()),
+                Diff::Added("本".to_string()),
+                Diff::Same("书".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_char_diff_added_removed_same() -> Result<()> {
+        // -- Exec & Check
+        assert_eq!(
+            char_diff("我去学校", "我昨天去了学校"),
+            vec![
+                Diff::Same("我".to_string()),
+                Diff::Added("昨天".to_string()),
+                Diff::Same("去".to_string()),
+                Diff::Added("了".to_string()),
+                Diff::Same("学校".to_string()),
+            ]
+        );
+        assert_eq!(
+            char_diff("很好", ""),
+            vec![Diff::Removed("很好".to_string())]
+        );
+        assert_eq!(char_diff("好", "好"), vec![Diff::Same("好".to_string())]);
+        assert!(char_diff("", "").is_empty());
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
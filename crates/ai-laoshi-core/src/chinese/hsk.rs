@@ -0,0 +1,379 @@
+// NOTE: The HSK level of the words of a Chinese text, from the official
+// HSK 2.0 (levels 1-6) or HSK 3.0 (levels 1-6, and 7-9 as one band) word
+// lists (no network). The HSK 2.0 lists are built in (src/chinese/hsk/2.0),
+// the HSK 3.0 ones are not (no copy with clear redistribution terms), so
+// they must be local files. The local files of the laoshi dir override the
+// built-in lists, by level:
+//
+// laoshi/hsk/2.0/1.txt ... 6.txt
+// laoshi/hsk/3.0/1.txt ... 6.txt, 7-9.txt
+//
+// One word per line (the first column of a TSV line, e.g., "爱	ài	love"),
+// the `#` lines are comments. A word of no list takes the highest level of
+// its listed parts (e.g., 一个, 三十五), and is "unlisted" only if one of
+// its parts is not listed (e.g., a name).
+use crate::chinese::segment::{segment, Token};
+use crate::chinese::Cedict;
+use crate::{Error, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// The default HSK lists dir (in the laoshi dir).
+pub const HSK_DIR: &str = "hsk";
+
+/// The level of the HSK 3.0 "7-9" band.
+const HSK3_BAND_LEVEL: u8 = 7;
+
+/// The built-in HSK 2.0 lists, by level.
+const HSK2_LISTS: &[(u8, &str)] = &[
+    (1, include_str!("hsk/2.0/1.txt")),
+    (2, include_str!("hsk/2.0/2.txt")),
+    (3, include_str!("hsk/2.0/3.txt")),
+    (4, include_str!("hsk/2.0/4.txt")),
+    (5, include_str!("hsk/2.0/5.txt")),
+    (6, include_str!("hsk/2.0/6.txt")),
+];
+
+/// The share of words above the learner level that asks for a rewrite.
+// NOTE: Not 0, so a rare word does not trigger it.
+const MAX_ABOVE_LEVEL_SHARE: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub enum HskVersion {
+    #[default]
+    #[serde(rename = "2.0")]
+    V2,
+    #[serde(rename = "3.0")]
+    V3,
+}
+
+#[derive(Debug)]
+pub struct HskLists {
+    version: HskVersion,
+    /// The lowest level of each word
+    levels: HashMap<String, u8>,
+    /// The longest listed word, in chars
+    max_word_chars: usize,
+}
+
+/// A hanzi word of an analyzed text.
+#[derive(Debug, Clone)]
+pub struct HskWord<'a> {
+    pub word: &'a str,
+    /// `None` for the unlisted words
+    pub level: Option<u8>,
+}
+
+#[derive(Debug)]
+pub struct HskAnalysis<'a> {
+    pub version: HskVersion,
+    /// All the tokens of the text (the levels are on the words)
+    pub tokens: Vec<(Token<'a>, Option<u8>)>,
+}
+
+impl HskVersion {
+    /// The levels of the version (7 is the 3.0 "7-9" band).
+    pub fn levels(&self) -> &'static [u8] {
+        match self {
+            HskVersion::V2 => &[1, 2, 3, 4, 5, 6],
+            HskVersion::V3 => &[1, 2, 3, 4, 5, 6, HSK3_BAND_LEVEL],
+        }
+    }
+
+    /// e.g., "HSK 4", "HSK 7-9"
+    pub fn level_name(&self, level: u8) -> String {
+        match (self, level) {
+            (HskVersion::V3, HSK3_BAND_LEVEL) => "HSK 7-9".to_string(),
+            _ => format!("HSK {level}"),
+        }
+    }
+
+    /// e.g., "2.0" (also the lists dir name)
+    pub fn name(&self) -> &'static str {
+        match self {
+            HskVersion::V2 => "2.0",
+            HskVersion::V3 => "3.0",
+        }
+    }
+
+    /// The built-in list of the `level`, if any (none for HSK 3.0).
+    fn builtin_list(&self, level: u8) -> Option<&'static str> {
+        match self {
+            HskVersion::V2 => HSK2_LISTS
+                .iter()
+                .find(|(l, _)| *l == level)
+                .map(|(_, content)| *content),
+            HskVersion::V3 => None,
+        }
+    }
+
+    fn file_name(&self, level: u8) -> String {
+        match (self, level) {
+            (HskVersion::V3, HSK3_BAND_LEVEL) => "7-9.txt".to_string(),
+            _ => format!("{level}.txt"),
+        }
+    }
+}
+
+impl HskLists {
+    /// Loads the lists of the `version`, the files of the HSK `dir` over the
+    /// built-in ones (fails if none).
+    pub fn load(dir: impl AsRef<Path>, version: HskVersion) -> Result<Self> {
+        let version_dir = dir.as_ref().join(version.name());
+        let mut lists = HskLists {
+            version,
+            levels: HashMap::new(),
+            max_word_chars: 0,
+        };
+
+        for level in version.levels() {
+            let file = version_dir.join(version.file_name(*level));
+            // NOTE: A missing level is fine (e.g., only HSK 1-3 downloaded)
+            match fs::read_to_string(&file) {
+                Ok(content) => lists.add(*level, &content),
+                Err(_) => {
+                    if let Some(content) = version.builtin_list(*level) {
+                        lists.add(*level, content);
+                    }
+                }
+            }
+        }
+
+        if lists.levels.is_empty() {
+            return Err(Error::HskListsNotFound(
+                version_dir.to_string_lossy().to_string(),
+            ));
+        }
+        Ok(lists)
+    }
+
+    /// Adds the words of a list `content` at the `level`.
+    pub fn add(&mut self, level: u8, content: &str) {
+        let words = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split(['\t', ' ', ',']).next())
+            .map(|word| word.trim_start_matches('\u{feff}').trim())
+            .filter(|word| !word.is_empty());
+        for word in words {
+            self.max_word_chars = self.max_word_chars.max(word.chars().count());
+            let entry = self.levels.entry(word.to_string()).or_insert(level);
+            *entry = (*entry).min(level);
+        }
+    }
+
+    pub fn version(&self) -> HskVersion {
+        self.version
+    }
+
+    /// The HSK level of the `word` (its simplified form if traditional), or
+    /// the highest level of its listed parts (`None` if a part is unlisted).
+    pub fn level(&self, word: &str, cedict: &Cedict) -> Option<u8> {
+        let entries = cedict.lookup(word);
+        let forms: Vec<&str> = std::iter::once(word)
+            .chain(entries.iter().map(|entry| entry.simplified.as_str()))
+            .collect();
+        forms
+            .iter()
+            .filter_map(|form| self.levels.get(*form))
+            .min()
+            .copied()
+            .or_else(|| forms.iter().filter_map(|form| self.parts_level(form)).min())
+    }
+
+    /// The highest level of the listed parts of the `word`, the longest
+    /// parts first, down to the chars (`None` if a char is unlisted).
+    fn parts_level(&self, word: &str) -> Option<u8> {
+        let mut level = None;
+        let mut rest = word;
+        while !rest.is_empty() {
+            // NOTE: The char boundaries, up to the longest listed word
+            let ends: Vec<usize> = rest
+                .char_indices()
+                .skip(1)
+                .map(|(idx, _)| idx)
+                .chain(std::iter::once(rest.len()))
+                .take(self.max_word_chars.max(1))
+                .collect();
+            let (end, part_level) = ends
+                .iter()
+                .rev()
+                .find_map(|end| Some((*end, *self.levels.get(&rest[..*end])?)))?;
+            level = level.max(Some(part_level));
+            rest = &rest[end..];
+        }
+        level
+    }
+}
+
+/// Segments the `text` (offline) and sets the HSK level of each word.
+pub fn analyze<'a>(
+    text: &'a str,
+    cedict: &Cedict,
+    lists: &HskLists,
+) -> HskAnalysis<'a> {
+    let tokens = segment(text, cedict)
+        .into_iter()
+        .map(|token| match token {
+            Token::Word(word) => (token, lists.level(word, cedict)),
+            Token::Text(_) => (token, None),
+        })
+        .collect();
+    HskAnalysis {
+        version: lists.version(),
+        tokens,
+    }
+}
+
+impl<'a> HskAnalysis<'a> {
+    /// The hanzi words, in the text order (repeated words included).
+    pub fn words(&self) -> Vec<HskWord<'a>> {
+        self.tokens
+            .iter()
+            .filter_map(|(token, level)| match token {
+                Token::Word(word) => Some(HskWord {
+                    word,
+                    level: *level,
+                }),
+                Token::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// The share (0.0 to 1.0) of the words at each level, by level
+    /// (`None` for the unlisted words).
+    pub fn shares(&self) -> BTreeMap<Option<u8>, f32> {
+        let words = self.words();
+        let mut counts: BTreeMap<Option<u8>, usize> = BTreeMap::new();
+        for word in &words {
+            *counts.entry(word.level).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(level, count)| (level, count as f32 / words.len() as f32))
+            .collect()
+    }
+
+    /// The distinct words above the learner `level` (not the unlisted ones).
+    pub fn above(&self, level: u8) -> Vec<HskWord<'a>> {
+        self.distinct_words(|word| is_above(word.level, level))
+    }
+
+    /// The distinct unlisted words (e.g., the names).
+    pub fn unlisted(&self) -> Vec<HskWord<'a>> {
+        self.distinct_words(|word| word.level.is_none())
+    }
+
+    /// `true` if too many of the listed words are above the learner `level`
+    /// (see `rewrite_request`).
+    pub fn goes_above(&self, level: u8) -> bool {
+        let levels: Vec<u8> = self.words().iter().filter_map(|w| w.level).collect();
+        let num_above = levels.iter().filter(|l| **l > level).count();
+        !levels.is_empty()
+            && num_above as f32 / levels.len() as f32 > MAX_ABOVE_LEVEL_SHARE
+    }
+
+    /// The message asking the laoshi to rewrite its last response at the
+    /// learner `level`.
+    pub fn rewrite_request(&self, level: u8) -> String {
+        let words: Vec<String> = self
+            .above(level)
+            .iter()
+            .filter_map(|w| {
+                let level_name = self.version.level_name(w.level?);
+                Some(format!("{} ({level_name})", w.word))
+            })
+            .collect();
+        format!(
+            "Please rewrite your last reply for my level, {} (HSK {} word lists). \
+             These words are above my level: {}. Use simpler words instead, \
+             or explain them briefly in English.",
+            self.version.level_name(level),
+            self.version.name(),
+            words.join(", ")
+        )
+    }
+
+    /// The distinct words matching the `filter`, in the text order.
+    fn distinct_words(&self, filter: impl Fn(&HskWord) -> bool) -> Vec<HskWord<'a>> {
+        let mut distinct: Vec<HskWord> = Vec::new();
+        for word in self.words() {
+            if filter(&word) && !distinct.iter().any(|w| w.word == word.word) {
+                distinct.push(word);
+            }
+        }
+        distinct
+    }
+}
+
+/// `true` if a word `level` is above the learner `level` (`false` for the
+/// unlisted words).
+pub fn is_above(word_level: Option<u8>, level: u8) -> bool {
+    word_level.is_some_and(|word_level| word_level > level)
+}
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    const FX_CEDICT: &str = "\
+一個 一个 [yi1 ge4] /one/
+王 王 [wang2] /king; surname Wang/
+朋友 朋友 [peng2 you5] /friend/
+學習 学习 [xue2 xi2] /to learn/
+";
+
+    #[test]
+    fn test_level_builtin_and_parts() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_cedict = Cedict::parse(FX_CEDICT);
+        let lists = HskLists::load("no-hsk-dir", HskVersion::V2)?;
+
+        // -- Exec & Check
+        assert_eq!(lists.level("学习", &fx_cedict), Some(1));
+        assert_eq!(lists.level("學習", &fx_cedict), Some(1));
+        // Not listed, the highest level of the parts
+        assert_eq!(lists.level("一个", &fx_cedict), Some(1));
+        assert_eq!(lists.level("三十五", &fx_cedict), Some(1));
+        assert_eq!(lists.level("学习环境", &fx_cedict), Some(3));
+        // A part (王) is not listed
+        assert_eq!(lists.level("王明", &fx_cedict), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_goes_above_unlisted_excluded() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_cedict = Cedict::parse(FX_CEDICT);
+        let lists = HskLists::load("no-hsk-dir", HskVersion::V2)?;
+
+        // -- Exec
+        let analysis = analyze("王明是我的朋友。", &fx_cedict, &lists);
+
+        // -- Check
+        assert!(!analysis.goes_above(1));
+        assert!(analysis.above(1).is_empty());
+        let unlisted: Vec<&str> =
+            analysis.unlisted().iter().map(|w| w.word).collect();
+        assert_eq!(unlisted, ["王", "明"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_v3_needs_files() -> Result<()> {
+        // -- Exec & Check
+        assert!(HskLists::load("no-hsk-dir", HskVersion::V3).is_err());
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
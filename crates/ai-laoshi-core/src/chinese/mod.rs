@@ -0,0 +1,41 @@
+//! The `chinese` module has the offline Chinese tools of the tutor mode:
+//! the CC-CEDICT dictionary (and its lookup), the word segmentation, the
+//! pinyin annotation, the Simplified/Traditional conversion, the HSK level
+//! analysis and the diff of the grammar corrections.
+//!
+//! Nothing here calls OpenAI (or the network), so the UIs (CLI, Tauri app)
+//! can use them directly on the laoshi responses.
+
+// region:       -- Modules
+
+mod cedict;
+mod convert;
+mod dict;
+mod diff;
+mod hsk;
+mod pinyin;
+mod script;
+mod segment;
+
+pub use cedict::{Cedict, CedictEntry, CEDICT_FILE};
+pub use convert::{ScriptConverter, OPENCC_DIR};
+pub use dict::{DictHit, DictMatch};
+pub use diff::{char_diff, Diff};
+pub use hsk::{
+    analyze, is_above, HskAnalysis, HskLists, HskVersion, HskWord, HSK_DIR,
+};
+pub use pinyin::{annotate, format_pinyin, to_tone_marks, Ruby, ToneStyle};
+pub use script::Script;
+pub use segment::{segment, Token};
+
+// endregion:    -- Modules
+
+/// `true` for the CJK ideographs (not the Chinese punctuation).
+pub fn is_hanzi(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}'     // CJK Unified Ideographs
+        | '\u{3400}'..='\u{4DBF}'   // Extension A
+        | '\u{20000}'..='\u{2A6DF}' // Extension B
+        | '\u{F900}'..='\u{FAFF}'   // Compatibility Ideographs
+    )
+}
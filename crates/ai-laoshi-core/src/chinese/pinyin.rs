@@ -0,0 +1,202 @@
+// NOTE: Pinyin annotation of the Chinese words of a text (e.g., a laoshi
+// response), with tone marks (nǐhǎo) or tone numbers (ni3hao3). The UIs
+// render the annotations (e.g., ruby lines above the hanzi, or brackets).
+use crate::chinese::segment::{segment, Token};
+use crate::chinese::Cedict;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToneStyle {
+    /// nǐhǎo
+    #[default]
+    Marks,
+    /// ni3hao3
+    Numbers,
+}
+
+/// A piece of an annotated text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ruby<'a> {
+    pub text: &'a str,
+    /// The pinyin of a hanzi word (`None` for the other texts and the
+    /// hanzi not in the dictionary)
+    pub pinyin: Option<String>,
+}
+
+/// Annotates the hanzi words of the `text` with their pinyin.
+pub fn annotate<'a>(
+    text: &'a str,
+    dict: &Cedict,
+    tones: ToneStyle,
+) -> Vec<Ruby<'a>> {
+    segment(text, dict)
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) => Ruby { text, pinyin: None },
+            Token::Word(word) => Ruby {
+                text: word,
+                pinyin: dict.reading(word).map(|p| format_pinyin(p, tones)),
+            },
+        })
+        .collect()
+}
+
+/// Formats the numbered pinyin of a word (e.g., "Xi1 an1" -> "Xī'ān").
+pub fn format_pinyin(numbered: &str, tones: ToneStyle) -> String {
+    let mut word = String::new();
+    for syllable in numbered.split_whitespace() {
+        let syllable = match tones {
+            ToneStyle::Marks => to_tone_marks(syllable),
+            ToneStyle::Numbers => syllable.replace("u:", "ü").replace("U:", "Ü"),
+        };
+        // NOTE: An apostrophe before a syllable starting with a, e or o
+        let starts_with_aeo = syllable
+            .chars()
+            .next()
+            .is_some_and(|c| "aeoāáǎàēéěèōóǒò".contains(c));
+        if !word.is_empty() && starts_with_aeo {
+            word.push('\'');
+        }
+        word.push_str(&syllable);
+    }
+    word
+}
+
+/// Converts a numbered syllable to tone marks (e.g., "lu:4" -> "lǜ").
+/// The neutral tone (5) has no mark, non-syllables are kept as is.
+pub fn to_tone_marks(syllable: &str) -> String {
+    let syllable = syllable.replace("u:", "ü").replace("U:", "Ü");
+    let Some(tone) = syllable
+        .chars()
+        .last()
+        .and_then(|c| c.to_digit(10))
+        .filter(|tone| (1..=5).contains(tone))
+    else {
+        return syllable;
+    };
+    let body = &syllable[..syllable.len() - 1];
+    if tone == 5 {
+        return body.to_string();
+    }
+
+    // -- The vowel taking the mark: a or e, the o of ou, else the last vowel
+    //    (the m or n of the vowel-less syllables, e.g., m2 -> ḿ, ng3 -> ňg)
+    let lower = body.to_lowercase();
+    let mark_idx = lower
+        .find(['a', 'e'])
+        .or_else(|| lower.find("ou"))
+        .or_else(|| lower.rfind(['i', 'o', 'u', 'ü']))
+        .or_else(|| lower.find(['m', 'n']));
+    let Some(mark_idx) = mark_idx else {
+        return body.to_string();
+    };
+
+    // NOTE: `to_lowercase` keeps the byte offsets for the pinyin letters
+    let mut marked = String::with_capacity(body.len() + 2);
+    for (idx, c) in body.char_indices() {
+        match with_tone_mark(c, tone).filter(|_| idx == mark_idx) {
+            Some(c_marked) => marked.push_str(c_marked),
+            None => marked.push(c),
+        }
+    }
+    marked
+}
+
+/// The letter with its tone mark (tones 1 to 4), `None` if not a pinyin
+/// vowel, m or n.
+// NOTE: Some m and n marks have no precomposed char (combining diacritics)
+fn with_tone_mark(letter: char, tone: u32) -> Option<&'static str> {
+    let marks = match letter {
+        'a' => ["ā", "á", "ǎ", "à"],
+        'e' => ["ē", "é", "ě", "è"],
+        'i' => ["ī", "í", "ǐ", "ì"],
+        'o' => ["ō", "ó", "ǒ", "ò"],
+        'u' => ["ū", "ú", "ǔ", "ù"],
+        'ü' => ["ǖ", "ǘ", "ǚ", "ǜ"],
+        'm' => ["m\u{304}", "ḿ", "m\u{30c}", "m\u{300}"],
+        'n' => ["n\u{304}", "ń", "ň", "ǹ"],
+        'A' => ["Ā", "Á", "Ǎ", "À"],
+        'E' => ["Ē", "É", "Ě", "È"],
+        'I' => ["Ī", "Í", "Ǐ", "Ì"],
+        'O' => ["Ō", "Ó", "Ǒ", "Ò"],
+        'U' => ["Ū", "Ú", "Ǔ", "Ù"],
+        'Ü' => ["Ǖ", "Ǘ", "Ǚ", "Ǜ"],
+        'M' => ["M\u{304}", "Ḿ", "M\u{30c}", "M\u{300}"],
+        'N' => ["N\u{304}", "Ń", "Ň", "Ǹ"],
+        _ => return None,
+    };
+    Some(marks[tone as usize - 1])
+}
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    #[test]
+    fn test_to_tone_marks_vowels() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_cases = [
+            ("ma1", "mā"),
+            ("hao3", "hǎo"),
+            ("xue2", "xué"),
+            ("gou3", "gǒu"),
+            ("gui4", "guì"),
+            ("liu2", "liú"),
+            ("lu:4", "lǜ"),
+            ("Zhong1", "Zhōng"),
+            ("ma5", "ma"),
+            ("r5", "r"),
+            ("xx5", "xx"),
+            ("abc", "abc"),
+        ];
+
+        // -- Exec & Check
+        for (fx_numbered, fx_marked) in fx_cases {
+            assert_eq!(to_tone_marks(fx_numbered), fx_marked, "{fx_numbered}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_tone_marks_nasals() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_cases = [
+            ("m2", "ḿ"),
+            ("n2", "ń"),
+            ("n3", "ň"),
+            ("n4", "ǹ"),
+            ("ng2", "ńg"),
+            ("ng4", "ǹg"),
+            ("hm5", "hm"),
+            ("m1", "m\u{304}"),
+            ("hng2", "hńg"),
+        ];
+
+        // -- Exec & Check
+        for (fx_numbered, fx_marked) in fx_cases {
+            assert_eq!(to_tone_marks(fx_numbered), fx_marked, "{fx_numbered}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_pinyin_marks_and_numbers() -> Result<()> {
+        // -- Exec & Check
+        assert_eq!(format_pinyin("ni3 hao3", ToneStyle::Marks), "nǐhǎo");
+        assert_eq!(format_pinyin("Xi1 an1", ToneStyle::Marks), "Xī'ān");
+        assert_eq!(format_pinyin("nu:3 er2", ToneStyle::Marks), "nǚ'ér");
+        assert_eq!(format_pinyin("nu:3 er2", ToneStyle::Numbers), "nü3'er2");
+        assert_eq!(format_pinyin("xue2 xi2", ToneStyle::Numbers), "xue2xi2");
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
@@ -0,0 +1,29 @@
+// NOTE: The Chinese scripts, for the learner preference (see the learner
+// profile) and the conversion of the responses (see `ScriptConverter`).
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Script {
+    #[default]
+    Simplified,
+    Traditional,
+}
+
+impl Script {
+    /// e.g., "simp", "simplified", "trad", "traditional"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "simp" | "simplified" => Some(Script::Simplified),
+            "trad" | "traditional" => Some(Script::Traditional),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Script::Simplified => "simplified",
+            Script::Traditional => "traditional",
+        }
+    }
+}
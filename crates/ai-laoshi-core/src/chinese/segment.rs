@@ -0,0 +1,114 @@
+// NOTE: Offline word segmentation of the Chinese runs of a text, by forward
+// maximum matching on the dictionary words (longest word first). Good enough
+// for the annotations; unknown hanzi are single-char words.
+use crate::chinese::{is_hanzi, Cedict};
+
+/// A piece of a segmented text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token<'a> {
+    /// Anything not hanzi (including the Chinese punctuation)
+    Text(&'a str),
+    /// One word of hanzi
+    Word(&'a str),
+}
+
+/// Splits the `text` into non-hanzi texts and hanzi words.
+pub fn segment<'a>(text: &'a str, dict: &Cedict) -> Vec<Token<'a>> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        // -- Text, up to the next hanzi
+        let hanzi_start = rest.find(is_hanzi).unwrap_or(rest.len());
+        if hanzi_start > 0 {
+            tokens.push(Token::Text(&rest[..hanzi_start]));
+            rest = &rest[hanzi_start..];
+            continue;
+        }
+
+        // -- The run of hanzi, split into words
+        let run_end = rest.find(|c: char| !is_hanzi(c)).unwrap_or(rest.len());
+        let mut run = &rest[..run_end];
+        while !run.is_empty() {
+            let word_end = longest_word_end(run, dict);
+            tokens.push(Token::Word(&run[..word_end]));
+            run = &run[word_end..];
+        }
+        rest = &rest[run_end..];
+    }
+
+    tokens
+}
+
+/// The byte end of the longest dictionary word at the start of the `run`
+/// (the first char if none).
+fn longest_word_end(run: &str, dict: &Cedict) -> usize {
+    // NOTE: The char boundaries, up to the longest dictionary word
+    let ends: Vec<usize> = run
+        .char_indices()
+        .skip(1)
+        .map(|(idx, _)| idx)
+        .chain(std::iter::once(run.len()))
+        .take(dict.max_word_chars().max(1))
+        .collect();
+
+    ends.iter()
+        .rev()
+        .find(|end| dict.contains(&run[..**end]))
+        .copied()
+        .unwrap_or(ends[0])
+}
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    const FX_CEDICT: &str = "\
+中國 中国 [Zhong1 guo2] /China/
+中國人 中国人 [Zhong1 guo2 ren2] /Chinese person/
+人 人 [ren2] /person/
+我 我 [wo3] /I; me/
+是 是 [shi4] /to be/
+";
+
+    #[test]
+    fn test_segment_longest_words() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_dict = Cedict::parse(FX_CEDICT);
+
+        // -- Exec
+        let tokens = segment("我是中国人。Hi 龘", &fx_dict);
+
+        // -- Check
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Word("我"),
+                Token::Word("是"),
+                Token::Word("中国人"),
+                Token::Text("。Hi "),
+                Token::Word("龘"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_segment_no_hanzi() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_dict = Cedict::parse(FX_CEDICT);
+
+        // -- Exec & Check
+        assert_eq!(segment("Hello!", &fx_dict), vec![Token::Text("Hello!")]);
+        assert!(segment("", &fx_dict).is_empty());
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
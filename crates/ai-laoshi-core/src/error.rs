@@ -3,7 +3,7 @@ use async_openai::error::OpenAIError;
 use async_openai::types::RunStatus;
 use derive_more::From;
 use std::io;
-use tokio::sync::broadcast;
+// use tokio::sync::broadcast;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -12,6 +12,36 @@ pub enum Error {
     // -- agent (laoshi)
     ShouldNotDeleteLocalFile(String),
     CannotFindThreadIdForConv(String),
+    ConfigToml {
+        file: String,
+        cause: toml::de::Error,
+    },
+    ConfigParamNotSupported(&'static str),
+    ConfigEnvValue {
+        var: String,
+        expected: String,
+    },
+    BundleNotFound(String),
+    AttachmentNotFound(String),
+    TooManyAttachments(usize),
+    ApiKeyCmdFailed {
+        cmd: String,
+        cause: String,
+    },
+    InstructionsFileNotFound(String),
+    InstructionsTemplate {
+        file: String,
+        cause: minijinja::Error,
+    },
+    CorrectionInvalidJson(String),
+    ProfileField(String),
+    ProfileValue(String),
+    QuizInvalidJson(String),
+    QuizNoQuestions,
+    //
+    // -- chinese
+    CedictNotFound(String),
+    HskListsNotFound(String),
     //
     // -- ais
     MessageImageNotSupported,
@@ -34,6 +64,14 @@ pub enum Error {
     SimpleFs(simple_fs::Error),
     #[from]
     OpenAI(OpenAIError),
+    #[from]
+    Reqwest(reqwest::Error),
+    #[cfg(feature = "anki")]
+    #[from]
+    Sqlite(rusqlite::Error),
+    #[cfg(feature = "anki")]
+    #[from]
+    Zip(zip::result::ZipError),
 }
 
 // region:    --- Error Boilerplate
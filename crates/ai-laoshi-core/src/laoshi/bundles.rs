@@ -0,0 +1,63 @@
+// NOTE: OpenAI does not let us download the assistant (retrieval) files, so
+// to know if a bundle changed since its upload, we keep the hash of each
+// uploaded bundle locally (laoshi/.laoshi/bundles.json).
+use crate::ais::AssistantId;
+use crate::laoshi::config::FileBundle;
+use crate::Result;
+use simple_fs::{load_json, save_json};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const BUNDLE_HASHES_JSON: &str = "bundles.json";
+
+/// The remote bundle file name, e.g., `laoshi-01-knowledge-bundle-asst_abc.md`
+pub(super) fn bundle_file_name(
+    laoshi_name: &str,
+    bundle: &FileBundle,
+    assistant_id: &AssistantId,
+) -> String {
+    format!(
+        "{}-{}-bundle-{}.{}",
+        laoshi_name,        // "laoshi-01"
+        bundle.bundle_name, // "knowledge"
+        assistant_id,       // "asst_..."
+        bundle.dst_ext,     // "md"
+    )
+}
+
+/// The local upload state of a config file bundle (see `Laoshi::bundle_states`).
+#[derive(Debug)]
+pub struct BundleState {
+    pub bundle_name: String,
+    /// The number of source files matching the bundle globs
+    pub num_files: usize,
+    /// `true` if the bundle has been uploaded for the current assistant
+    pub uploaded: bool,
+}
+
+/// The content hash of the uploaded bundles, by bundle file name.
+#[derive(Debug, Default)]
+pub(super) struct BundleHashes {
+    file: PathBuf,
+    hashes: HashMap<String, String>,
+}
+
+impl BundleHashes {
+    /// Loads the hashes from the laoshi `data_dir` (empty if none yet).
+    pub fn load(data_dir: &Path) -> Self {
+        let file = data_dir.join(BUNDLE_HASHES_JSON);
+        let hashes = load_json(&file).unwrap_or_default();
+        Self { file, hashes }
+    }
+
+    pub fn get(&self, bundle_file_name: &str) -> Option<&str> {
+        self.hashes.get(bundle_file_name).map(String::as_str)
+    }
+
+    /// Records the hash of an uploaded bundle and saves the hashes file.
+    pub fn record(&mut self, bundle_file_name: &str, hash: String) -> Result<()> {
+        self.hashes.insert(bundle_file_name.to_string(), hash);
+        save_json(&self.file, &self.hashes)?;
+        Ok(())
+    }
+}
@@ -1,14 +1,43 @@
 // NOTE: This is parsing our high-level laoshi.toml config with serde
+// (merged with the global config and env vars, see layers.rs)
+use async_openai::{config::OpenAIConfig, Client};
 use serde::Deserialize;
+use simple_fs::{list_files, read_to_string, SFile};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
 
-use crate::ais::assistant;
+use crate::ais::{assistant, new_openai_client};
+use crate::laoshi::layers::ConfigLayers;
+use crate::{Error, Result};
 
 // Q: What's the difference btw pub(super) and pub(crate)?
 #[derive(Debug, Deserialize)]
 pub(super) struct Config {
     pub name: String,
     pub model: String,
-    pub instructions_file: String,
+    // -- OpenAI endpoint & key (usually in the global config)
+    // NOTE: Key resolution order: api_key, api_key_file, api_key_cmd,
+    // and finally the OPENAI_API_KEY env var.
+    pub api_base: Option<String>,
+    pub api_key: Option<String>,
+    pub api_key_file: Option<String>,
+    pub api_key_cmd: Option<String>,
+    // -- Assistant
+    pub description: Option<String>,
+    // NOTE: [metadata] table of string values (max 16 keys on OpenAI)
+    pub metadata: Option<HashMap<String, String>>,
+    // NOTE: Not supported by the Assistants v1 API (async-openai 0.18),
+    // neither on the assistant nor per run (`CreateRunRequest`), but parsed
+    // so we can fail loudly instead of silently ignoring them.
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub response_format: Option<toml::Value>,
+    pub instructions_file: Option<String>,
+    // NOTE: Fragments composed (in order) after `instructions_file`.
+    // Paths are relative to this .toml file (e.g., "../shared/style-guide.md")
+    #[serde(default)]
+    pub instructions_files: Vec<String>,
     pub file_bundles: Vec<FileBundle>,
     // NOTE: This file_bundles Vec<FileBundle> corresponds to our laoshi.toml properties:
     // [[file_bundles]]
@@ -26,6 +55,118 @@ pub(super) struct FileBundle {
     pub dst_ext: String,
 }
 
+impl Config {
+    /// Loads the layered config (global, `dir`/`project_toml`, env).
+    pub fn load(dir: &Path, project_toml: &str) -> Result<Self> {
+        let layers = ConfigLayers::load(dir, project_toml)?;
+        let config =
+            layers
+                .to_table()
+                .try_into()
+                .map_err(|cause| Error::ConfigToml {
+                    file: project_toml.to_string(),
+                    cause,
+                })?;
+        Ok(config)
+    }
+
+    pub fn new_openai_client(&self) -> Result<Client<OpenAIConfig>> {
+        new_openai_client(self.resolve_api_key()?, self.api_base.as_deref())
+    }
+
+    /// Fails if the config sets a model parameter the assistant API
+    /// version we use cannot apply.
+    pub fn validate(&self) -> Result<()> {
+        let unsupported = [
+            ("temperature", self.temperature.is_some()),
+            ("top_p", self.top_p.is_some()),
+            ("response_format", self.response_format.is_some()),
+        ];
+        match unsupported.into_iter().find(|(_, is_set)| *is_set) {
+            Some((param, _)) => Err(Error::ConfigParamNotSupported(param)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the api key from `api_key`, `api_key_file` or `api_key_cmd`
+    /// (in this order), or `None` to fallback to the env var.
+    fn resolve_api_key(&self) -> Result<Option<String>> {
+        if let Some(api_key) = self.api_key.as_ref() {
+            return Ok(Some(api_key.clone()));
+        }
+
+        if let Some(api_key_file) = self.api_key_file.as_ref() {
+            let api_key = read_to_string(expand_home(api_key_file))?;
+            return Ok(Some(api_key.trim().to_string()));
+        }
+
+        if let Some(api_key_cmd) = self.api_key_cmd.as_ref() {
+            // NOTE: Through the shell, so `pass show openai | head -1` works.
+            let output = Command::new("sh").arg("-c").arg(api_key_cmd).output()?;
+            if !output.status.success() {
+                return Err(Error::ApiKeyCmdFailed {
+                    cmd: api_key_cmd.clone(),
+                    cause: String::from_utf8_lossy(&output.stderr)
+                        .trim()
+                        .to_string(),
+                });
+            }
+            let api_key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            return Ok(Some(api_key));
+        }
+
+        Ok(None)
+    }
+
+    /// All the instructions fragment files, in composition order.
+    pub fn instructions_files(&self) -> Vec<&str> {
+        self.instructions_file
+            .iter()
+            .chain(self.instructions_files.iter())
+            .map(AsRef::as_ref)
+            .collect()
+    }
+}
+
+impl FileBundle {
+    /// The files matching the bundle `src_globs` (empty if no `src_dir`).
+    /// - `base_dir` is the laoshi dir the `src_dir` is relative to.
+    pub fn src_files(&self, base_dir: &Path) -> Result<Vec<SFile>> {
+        // Get the specific bundle's src_dir (e.g, "laoshi/files", "crates")
+        let src_dir = base_dir.join(&self.src_dir);
+
+        // Check that we have an existing dir
+        if !src_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        // NOTE: Get our src_globs (e.g., ["**/*.rs] or ["*.md"]) as a Vec<&str>
+        // so we can pass as a slice of ref of String (&[&str]) needed for list_files() below.
+        // Q: How to use map() to return &str from String? Any difference
+        // between these approaches?
+        // bundle.src_globs.iter().map(|g| g.as_ref()).collect();
+        // bundle.src_globs.iter().map(|g| AsRef::as_ref(g)).collect();
+        let src_globs: Vec<&str> =
+            self.src_globs.iter().map(AsRef::as_ref).collect();
+
+        let files = list_files(&src_dir, Some(&src_globs), None)?;
+
+        Ok(files)
+    }
+}
+
+// region:       -- Support
+
+/// Expands the leading `~/` to the HOME dir.
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+// endregion:    -- Support
+
 // region:       -- Froms
 // NOTE: By design, this is separate from our higher-level 'Laoshi'
 // module configuration abstraction (see laoshi/config.rs), which itself
@@ -37,6 +178,8 @@ impl From<&Config> for assistant::CreateConfig {
         Self {
             name: config.name.clone(),
             model: config.model.clone(),
+            description: config.description.clone(),
+            metadata: config.metadata.clone(),
         }
     }
 }
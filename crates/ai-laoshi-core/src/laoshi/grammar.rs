@@ -0,0 +1,219 @@
+// NOTE: The grammar correction mode: the Chinese the learner writes is
+// corrected by the laoshi (on its own thread, deleted after the correction,
+// so the conversation is not polluted), with the errors by category and an
+// explanation. The UIs show the correction as a diff of the learner text
+// (see `chinese::char_diff`).
+//
+// The errors are kept in laoshi/.laoshi/grammar.json, for the recurring
+// mistakes (see `GrammarLog::stats`, shown by `laoshi grammar stats`).
+use crate::ais::assistant;
+use crate::chinese::is_hanzi;
+use crate::laoshi::{data_dir, Conversation, Laoshi};
+use crate::utils::json::extract_json;
+use crate::{Error, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use simple_fs::{load_json, save_json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const GRAMMAR_JSON: &str = "grammar.json";
+
+// region:       -- Types
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Correction {
+    /// The corrected text (the same text if no error)
+    pub corrected: String,
+    #[serde(default)]
+    pub errors: Vec<GrammarError>,
+    #[serde(default)]
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrammarError {
+    pub category: ErrorCategory,
+    /// The wrong part of the text
+    pub wrong: String,
+    /// Its correction
+    pub right: String,
+    #[serde(default)]
+    pub explanation: String,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    MeasureWord,
+    WordOrder,
+    Particle,
+    ToneCharacter,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default)]
+pub struct GrammarLog {
+    file: PathBuf,
+    entries: Vec<GrammarEntry>,
+}
+
+/// A corrected text with errors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrammarEntry {
+    pub date: DateTime<Local>,
+    pub text: String,
+    pub corrected: String,
+    pub errors: Vec<GrammarError>,
+}
+
+/// The errors of a category over time.
+#[derive(Debug)]
+pub struct CategoryStats {
+    pub category: ErrorCategory,
+    pub count: usize,
+    pub last_date: DateTime<Local>,
+}
+
+/// A mistake made more than once (e.g., "个" instead of "本").
+#[derive(Debug)]
+pub struct RecurringError {
+    pub category: ErrorCategory,
+    pub wrong: String,
+    pub right: String,
+    pub count: usize,
+}
+
+// endregion:    -- Types
+
+impl Laoshi {
+    /// The correction of the learner `text`, `None` if it has no Chinese.
+    pub async fn correct(&self, text: &str) -> Result<Option<Correction>> {
+        if !text.chars().any(is_hanzi) {
+            return Ok(None);
+        }
+
+        let mut conversation: Conversation =
+            assistant::create_thread(&self.oac).await?.into();
+        let msg = format!(
+            "Correct the Chinese I wrote below (grammar, measure words, word order, \
+             particles, wrong characters). Do not change what is already correct.\n\
+             Respond ONLY with a JSON object, no other text:\n\
+             {{\"corrected\": \"the corrected text (the same if no error)\", \
+             \"errors\": [{{\"category\": \"measure_word\" or \"word_order\" or \
+             \"particle\" or \"tone_character\" or \"other\", \
+             \"wrong\": \"the wrong part\", \"right\": \"its correction\", \
+             \"explanation\": \"why\"}}], \
+             \"explanation\": \"a short overall explanation\"}}\n\n\
+             What I wrote:\n{text}"
+        );
+        let res = self.chat(&mut conversation, &msg).await;
+        // NOTE: A one-off thread, deleted once answered (or failed)
+        self.delete_conversation(&conversation).await;
+        let res = res?;
+
+        let correction: Correction =
+            extract_json(&res).ok_or(Error::CorrectionInvalidJson(res))?;
+        Ok(Some(correction))
+    }
+}
+
+impl Correction {
+    /// `true` if the text had no error.
+    pub fn is_correct(&self, text: &str) -> bool {
+        self.errors.is_empty() && self.corrected.trim() == text.trim()
+    }
+}
+
+impl ErrorCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorCategory::MeasureWord => "measure word",
+            ErrorCategory::WordOrder => "word order",
+            ErrorCategory::Particle => "particle",
+            ErrorCategory::ToneCharacter => "tone/character",
+            ErrorCategory::Other => "other",
+        }
+    }
+}
+
+impl GrammarLog {
+    /// Loads the log of the laoshi `dir` (empty if none yet).
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let file = data_dir(dir.as_ref())?.join(GRAMMAR_JSON);
+        let entries = match file.is_file() {
+            true => load_json(&file)?,
+            false => Vec::new(),
+        };
+        Ok(Self { file, entries })
+    }
+
+    /// Adds the errors of the `correction` of the `text` (if any) and saves
+    /// the log file.
+    pub fn record(&mut self, text: &str, correction: &Correction) -> Result<()> {
+        if correction.errors.is_empty() {
+            return Ok(());
+        }
+        self.entries.push(GrammarEntry {
+            date: Local::now(),
+            text: text.to_string(),
+            corrected: correction.corrected.clone(),
+            errors: correction.errors.clone(),
+        });
+        save_json(&self.file, &self.entries)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[GrammarEntry] {
+        &self.entries
+    }
+
+    /// The stats by category (most frequent first).
+    pub fn stats(&self) -> Vec<CategoryStats> {
+        let mut by_category: BTreeMap<ErrorCategory, CategoryStats> =
+            BTreeMap::new();
+        for entry in &self.entries {
+            for error in &entry.errors {
+                let stats = by_category.entry(error.category).or_insert_with(|| {
+                    CategoryStats {
+                        category: error.category,
+                        count: 0,
+                        last_date: entry.date,
+                    }
+                });
+                stats.count += 1;
+                stats.last_date = stats.last_date.max(entry.date);
+            }
+        }
+
+        let mut stats: Vec<CategoryStats> = by_category.into_values().collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.count));
+        stats
+    }
+
+    /// The mistakes made more than once (most frequent first).
+    pub fn recurring(&self) -> Vec<RecurringError> {
+        let mut counts: BTreeMap<(ErrorCategory, &str, &str), usize> =
+            BTreeMap::new();
+        for error in self.entries.iter().flat_map(|entry| &entry.errors) {
+            let key = (error.category, error.wrong.trim(), error.right.trim());
+            *counts.entry(key).or_default() += 1;
+        }
+
+        let mut recurring: Vec<RecurringError> = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|((category, wrong, right), count)| RecurringError {
+                category,
+                wrong: wrong.to_string(),
+                right: right.to_string(),
+                count,
+            })
+            .collect();
+        recurring.sort_by_key(|r| std::cmp::Reverse(r.count));
+        recurring
+    }
+}
@@ -0,0 +1,180 @@
+// NOTE: The instructions files (e.g., laoshi/instructions.md) are rendered as
+// minijinja templates before being uploaded, so they can reference the laoshi
+// config, bundles, git state, etc. instead of being hand-edited.
+// Fragments can also `{% include "../shared/style-guide.md" %}` other files,
+// relative to the including file, so several laoshi dirs can share them.
+// REF: https://docs.rs/minijinja/latest/minijinja/syntax/index.html
+use crate::laoshi::config::Config;
+use crate::utils::files::{files_tree, BUNDLE_FILE_DELIMITER};
+use crate::{Error, Result};
+use minijinja::{Environment, ErrorKind, UndefinedBehavior};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::Path;
+use std::process::Command;
+
+// region:       -- Types
+
+/// The variables available inside the instructions templates.
+#[derive(Debug, Serialize)]
+pub(super) struct IxContext<'a> {
+    pub name: &'a str,
+    pub model: &'a str,
+    /// Today as `YYYY-MM-DD`
+    pub date: String,
+    /// `None` when the laoshi dir is not in a git repository
+    pub git: Option<GitInfo>,
+    pub bundles: Vec<BundleInfo<'a>>,
+    /// The line prefix delimiting each file inside a bundle file
+    pub file_delimiter: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct GitInfo {
+    pub branch: String,
+    pub commit: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct BundleInfo<'a> {
+    pub name: &'a str,
+    pub src_dir: &'a str,
+    pub dst_ext: &'a str,
+    pub file_count: usize,
+    /// The indented file tree of the bundle `src_dir`
+    pub tree: String,
+}
+
+// endregion:    -- Types
+
+// region:       -- Compose
+
+/// Renders and joins all the instructions files of the laoshi `dir`.
+/// Returns `None` if no instructions files are configured.
+pub(super) fn compose(dir: &Path, config: &Config) -> Result<Option<String>> {
+    let ix_files = config.instructions_files();
+    if ix_files.is_empty() {
+        return Ok(None);
+    }
+    // NOTE: A missing fragment (e.g., a typo) would upload partial instructions
+    if let Some(missing) = ix_files.iter().find(|file| !dir.join(file).is_file()) {
+        return Err(Error::InstructionsFileNotFound(missing.to_string()));
+    }
+
+    let ctx = build_context(dir, config)?;
+    let env = new_env(dir);
+
+    let mut fragments = Vec::new();
+    for ix_file in ix_files {
+        let fragment = env
+            .get_template(ix_file)
+            .and_then(|tmpl| tmpl.render(&ctx))
+            .map_err(|cause| Error::InstructionsTemplate {
+                file: ix_file.to_string(),
+                cause,
+            })?;
+        fragments.push(fragment.trim_end().to_string());
+    }
+
+    Ok(Some(format!("{}\n", fragments.join("\n\n"))))
+}
+
+/// The template environment, loading templates by path relative to `dir`.
+///
+/// NOTE: Undefined variables are an error (strict mode), so a typo in the
+/// templates fails before anything gets uploaded.
+fn new_env(dir: &Path) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+
+    let dir = dir.to_path_buf();
+    env.set_loader(move |name| {
+        let file = dir.join(name);
+        if !file.is_file() {
+            return Ok(None);
+        }
+        std::fs::read_to_string(&file).map(Some).map_err(|err| {
+            minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                format!("cannot read '{}'", file.display()),
+            )
+            .with_source(err)
+        })
+    });
+
+    // Include paths are relative to the including template
+    env.set_path_join_callback(|name, parent| {
+        let parent_dir = Path::new(parent).parent().unwrap_or(Path::new(""));
+        Cow::Owned(join_normalized(parent_dir, name))
+    });
+
+    env
+}
+
+/// Lexically joins `name` to `dir`, resolving `.` and `..` (keeping the
+/// leading `..`, since the laoshi dir can include from its parent dirs).
+fn join_normalized(dir: &Path, name: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in dir.iter().chain(Path::new(name).iter()) {
+        match segment.to_str().unwrap_or_default() {
+            "." => (),
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    segments.join("/")
+}
+
+// endregion:    -- Compose
+
+// region:       -- Context Helpers
+
+fn build_context<'a>(dir: &Path, config: &'a Config) -> Result<IxContext<'a>> {
+    let mut bundles = Vec::new();
+    for bundle in config.file_bundles.iter() {
+        let files = bundle.src_files(dir)?;
+        bundles.push(BundleInfo {
+            name: &bundle.bundle_name,
+            src_dir: &bundle.src_dir,
+            dst_ext: &bundle.dst_ext,
+            file_count: files.len(),
+            tree: files_tree(&dir.join(&bundle.src_dir), &files),
+        });
+    }
+
+    Ok(IxContext {
+        name: &config.name,
+        model: &config.model,
+        date: today(),
+        git: git_info(dir),
+        bundles,
+        file_delimiter: BUNDLE_FILE_DELIMITER,
+    })
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// Returns the current branch and short commit of the repo containing `dir`.
+fn git_info(dir: &Path) -> Option<GitInfo> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output();
+        match output {
+            Ok(output) if output.status.success() => {
+                Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+            }
+            _ => None,
+        }
+    };
+
+    Some(GitInfo {
+        branch: git(&["rev-parse", "--abbrev-ref", "HEAD"])?,
+        commit: git(&["rev-parse", "--short", "HEAD"])?,
+    })
+}
+
+// endregion:    -- Context Helpers
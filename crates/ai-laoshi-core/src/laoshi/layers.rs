@@ -0,0 +1,458 @@
+// NOTE: The laoshi config is layered (last one wins, per key):
+// 1. Global  - ~/.config/laoshi/config.toml (api endpoint, default model, keys, theme)
+// 2. Project - laoshi/laoshi.toml
+// 3. Env     - LAOSHI_* env vars (e.g., LAOSHI_MODEL, LAOSHI_THEME__PROMPT for theme.prompt)
+// Tables are merged per key (e.g., [theme]), other values (arrays included) are replaced.
+// Only the known config keys are taken from the env (see `ENV_KEYS`), parsed by
+// the type of their config field (e.g., LAOSHI_NAME=123 is the string "123").
+use crate::{Error, Result};
+use derive_more::Display;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+const GLOBAL_CONFIG_TOML: &str = "laoshi/config.toml";
+const ENV_PREFIX: &str = "LAOSHI_";
+/// The nested key separator in env var names (LAOSHI_THEME__PROMPT)
+const ENV_KEY_SEP: &str = "__";
+
+/// The keys never displayed in clear (see `ConfigLayers::entries`)
+const SECRET_KEYS: &[&str] = &["api_key"];
+
+/// The config keys settable by env var, with their type (`*` is any table key).
+/// NOTE: Keep in sync with the config fields (other LAOSHI_* vars are ignored).
+const ENV_KEYS: &[(&str, EnvKind)] = &[
+    ("name", EnvKind::Str),
+    ("model", EnvKind::Str),
+    ("api_base", EnvKind::Str),
+    ("api_key", EnvKind::Str),
+    ("api_key_file", EnvKind::Str),
+    ("api_key_cmd", EnvKind::Str),
+    ("description", EnvKind::Str),
+    ("metadata.*", EnvKind::Str),
+    ("temperature", EnvKind::Float),
+    ("top_p", EnvKind::Float),
+    ("instructions_file", EnvKind::Str),
+    ("instructions_files", EnvKind::StrList),
+    ("theme.ascii", EnvKind::Bool),
+    ("theme.prompt", EnvKind::Str),
+    ("theme.colors.*", EnvKind::Color),
+    ("theme.icons.*", EnvKind::Str),
+    ("tutor.pinyin", EnvKind::OneOf(&["off", "ruby", "inline"])),
+    ("tutor.tones", EnvKind::OneOf(&["marks", "numbers"])),
+    ("tutor.cedict_file", EnvKind::Str),
+    ("tutor.auto_save", EnvKind::Bool),
+    ("tutor.hsk_level", EnvKind::Int),
+    ("tutor.hsk_version", EnvKind::OneOf(&["2.0", "3.0"])),
+    ("tutor.hsk_dir", EnvKind::Str),
+    ("tutor.hsk_rewrite", EnvKind::Bool),
+    ("tutor.correct", EnvKind::Bool),
+    ("tutor.opencc_dir", EnvKind::Str),
+];
+
+// region:       -- Types
+
+/// Where a config value comes from.
+#[derive(Debug, Clone, Display)]
+pub enum ConfigSource {
+    #[display("global ({})", _0.display())]
+    Global(PathBuf),
+    #[display("project ({})", _0.display())]
+    Project(PathBuf),
+    #[display("env ({_0})")]
+    Env(String),
+}
+
+/// One merged config value with its source.
+#[derive(Debug)]
+pub struct ConfigEntry<'a> {
+    /// Dotted key path, e.g., `theme.prompt`
+    pub key: String,
+    /// The TOML representation (secrets masked)
+    pub value: String,
+    pub source: &'a ConfigSource,
+}
+
+/// The merged config values (by key path), with their source.
+#[derive(Debug, Default)]
+pub struct ConfigLayers {
+    values: BTreeMap<Vec<String>, (Value, ConfigSource)>,
+}
+
+/// The type of a config value set by env var.
+#[derive(Debug, Clone, Copy)]
+enum EnvKind {
+    /// Taken as is (never parsed)
+    Str,
+    Bool,
+    Int,
+    Float,
+    /// A TOML array of strings (e.g., `["a.md", "b.md"]`)
+    StrList,
+    /// A 256-color index (e.g., `45`) or a color name (e.g., `green`)
+    Color,
+    /// One of these strings (e.g., an enum value)
+    OneOf(&'static [&'static str]),
+}
+
+// endregion:    -- Types
+
+impl ConfigLayers {
+    /// Loads and merges the global, project (`dir`/laoshi.toml) and env layers.
+    pub fn load(dir: &Path, project_toml: &str) -> Result<Self> {
+        let mut layers = ConfigLayers::default();
+
+        // -- Global
+        if let Some(global_file) = global_config_file().filter(|f| f.is_file()) {
+            let table = load_table(&global_file)?;
+            layers.merge(table, &ConfigSource::Global(global_file));
+        }
+
+        // -- Project
+        let project_file = dir.join(project_toml);
+        let table = load_table(&project_file)?;
+        layers.merge(table, &ConfigSource::Project(project_file));
+
+        // -- Env
+        layers.merge_env(std::env::vars())?;
+
+        Ok(layers)
+    }
+
+    /// The merged values as one table (to deserialize the `Config`).
+    pub fn to_table(&self) -> Table {
+        let mut root = Table::new();
+        for (path, (value, _)) in self.values.iter() {
+            insert_at(&mut root, path, value.clone());
+        }
+        root
+    }
+
+    /// The merged `key` table (e.g., `theme`), deserialized (default if none).
+    pub fn section<T: DeserializeOwned + Default>(
+        &self,
+        key: &str,
+        project_toml: &str,
+    ) -> Result<T> {
+        let Some(section) = self.to_table().remove(key) else {
+            return Ok(T::default());
+        };
+        section.try_into().map_err(|cause| Error::ConfigToml {
+            file: project_toml.to_string(),
+            cause,
+        })
+    }
+
+    /// The merged values with their source, sorted by key (secrets masked).
+    pub fn entries(&self) -> Vec<ConfigEntry<'_>> {
+        self.values
+            .iter()
+            .map(|(path, (value, source))| {
+                let key = path.join(".");
+                let value = if SECRET_KEYS.contains(&key.as_str()) {
+                    "\"****\"".to_string()
+                } else {
+                    value.to_string()
+                };
+                ConfigEntry { key, value, source }
+            })
+            .collect()
+    }
+
+    // -- Private functions
+    fn merge(&mut self, table: Table, source: &ConfigSource) {
+        for (path, value) in flatten(table, Vec::new()) {
+            self.insert(path, value, source.clone());
+        }
+    }
+
+    /// Merges the `LAOSHI_*` vars of the known config keys (see `ENV_KEYS`).
+    fn merge_env(
+        &mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<()> {
+        let mut env_vars: Vec<(String, String)> = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        env_vars.sort();
+        for (name, raw_value) in env_vars {
+            let path: Vec<String> = name[ENV_PREFIX.len()..]
+                .split(ENV_KEY_SEP)
+                .map(str::to_lowercase)
+                .collect();
+            // NOTE: Not a config key (e.g., LAOSHI_DEBUG), so not ours
+            let Some(kind) = env_kind(&path) else {
+                continue;
+            };
+            let value =
+                kind.parse(&raw_value)
+                    .ok_or_else(|| Error::ConfigEnvValue {
+                        var: name.clone(),
+                        expected: kind.expected(),
+                    })?;
+            self.insert(path, value, ConfigSource::Env(name));
+        }
+
+        Ok(())
+    }
+
+    fn insert(&mut self, path: Vec<String>, value: Value, source: ConfigSource) {
+        // -- Remove the overridden ancestor leaf & descendant leaves
+        // (e.g., `theme = "plain"` overridden by `theme.prompt = ">"`)
+        self.values.retain(|other, _| {
+            !(other.starts_with(&path) || path.starts_with(other.as_slice()))
+        });
+        self.values.insert(path, (value, source));
+    }
+}
+
+// region:       -- Support
+
+/// The global config file, e.g., ~/.config/laoshi/config.toml
+fn global_config_file() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| Path::new(&home).join(".config"))
+        })?;
+    Some(config_dir.join(GLOBAL_CONFIG_TOML))
+}
+
+fn load_table(file: &Path) -> Result<Table> {
+    let content = simple_fs::read_to_string(file)?;
+    content.parse::<Table>().map_err(|cause| Error::ConfigToml {
+        file: file.to_string_lossy().to_string(),
+        cause,
+    })
+}
+
+/// Flattens the nested tables into the leaf values by key path.
+fn flatten(table: Table, prefix: Vec<String>) -> Vec<(Vec<String>, Value)> {
+    let mut leaves = Vec::new();
+    for (key, value) in table {
+        let mut path = prefix.clone();
+        path.push(key);
+        match value {
+            Value::Table(table) => leaves.extend(flatten(table, path)),
+            value => leaves.push((path, value)),
+        }
+    }
+    leaves
+}
+
+/// Inserts the `value` at the key `path`, creating the intermediate tables.
+fn insert_at(table: &mut Table, path: &[String], value: Value) {
+    match path {
+        [] => (),
+        [leaf] => {
+            table.insert(leaf.clone(), value);
+        }
+        [parent, rest @ ..] => {
+            let entry = table
+                .entry(parent.clone())
+                .or_insert_with(|| Value::Table(Table::new()));
+            // NOTE: Conflicting leaf/table were removed in `insert`
+            if let Value::Table(child) = entry {
+                insert_at(child, rest, value);
+            }
+        }
+    }
+}
+
+/// The type of the config key `path` (`None` if not settable by env var).
+fn env_kind(path: &[String]) -> Option<EnvKind> {
+    ENV_KEYS.iter().find_map(|(key, kind)| {
+        let key_path: Vec<&str> = key.split('.').collect();
+        let is_match = key_path.len() == path.len()
+            && key_path
+                .iter()
+                .zip(path)
+                .all(|(key_part, part)| *key_part == "*" || key_part == part);
+        is_match.then_some(*kind)
+    })
+}
+
+impl EnvKind {
+    /// The `raw_value` as this type of TOML value (`None` if invalid).
+    fn parse(self, raw_value: &str) -> Option<Value> {
+        match self {
+            EnvKind::Str => Some(Value::String(raw_value.to_string())),
+            EnvKind::Bool => {
+                raw_value.trim().parse::<bool>().ok().map(Value::Boolean)
+            }
+            EnvKind::Int => raw_value.trim().parse::<i64>().ok().map(Value::Integer),
+            EnvKind::Float => raw_value.trim().parse::<f64>().ok().map(Value::Float),
+            EnvKind::StrList => {
+                let value = format!("value = {raw_value}")
+                    .parse::<Table>()
+                    .ok()?
+                    .remove("value")?;
+                let is_str_list = value
+                    .as_array()
+                    .is_some_and(|items| items.iter().all(Value::is_str));
+                is_str_list.then_some(value)
+            }
+            EnvKind::Color => match raw_value.trim().parse::<u8>() {
+                Ok(index) => Some(Value::Integer(index.into())),
+                Err(_) => Some(Value::String(raw_value.to_string())),
+            },
+            EnvKind::OneOf(values) => values
+                .contains(&raw_value)
+                .then(|| Value::String(raw_value.to_string())),
+        }
+    }
+
+    /// The expected value, for the error message.
+    fn expected(self) -> String {
+        match self {
+            EnvKind::Str | EnvKind::Color => "a string".to_string(),
+            EnvKind::Bool => "true or false".to_string(),
+            EnvKind::Int => "an integer (e.g., 3)".to_string(),
+            EnvKind::Float => "a number (e.g., 0.2)".to_string(),
+            EnvKind::StrList => {
+                r#"an array of strings (e.g., ["a.md", "b.md"])"#.to_string()
+            }
+            EnvKind::OneOf(values) => format!("one of: {}", values.join(", ")),
+        }
+    }
+}
+
+// endregion:    -- Support
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    const FX_GLOBAL_TOML: &str = r#"
+name = "global-laoshi"
+model = "gpt-4"
+api_base = "https://proxy.example.com/v1"
+[metadata]
+team = "platform"
+"#;
+
+    const FX_PROJECT_TOML: &str = r#"
+name = "project-laoshi"
+[metadata]
+project = "crate"
+"#;
+
+    fn fx_env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_layers_merge_order() -> Result<()> {
+        // -- Setup & Fixtures
+        let mut layers = ConfigLayers::default();
+        let fx_global = ConfigSource::Global(PathBuf::from("global.toml"));
+        let fx_project = ConfigSource::Project(PathBuf::from("laoshi.toml"));
+
+        // -- Exec
+        layers.merge(FX_GLOBAL_TOML.parse()?, &fx_global);
+        layers.merge(FX_PROJECT_TOML.parse()?, &fx_project);
+        layers.merge_env(fx_env(&[("LAOSHI_MODEL", "gpt-4o")]))?;
+
+        // -- Check
+        let table = layers.to_table();
+        assert_eq!(table["name"].as_str(), Some("project-laoshi"));
+        assert_eq!(table["model"].as_str(), Some("gpt-4o"));
+        assert_eq!(
+            table["api_base"].as_str(),
+            Some("https://proxy.example.com/v1")
+        );
+        // the tables are merged per key
+        assert_eq!(table["metadata"]["team"].as_str(), Some("platform"));
+        assert_eq!(table["metadata"]["project"].as_str(), Some("crate"));
+        let sources: Vec<(String, String)> = layers
+            .entries()
+            .into_iter()
+            .map(|entry| (entry.key, entry.source.to_string()))
+            .collect();
+        assert!(sources.contains(&("name".into(), "project (laoshi.toml)".into())));
+        assert!(sources.contains(&("model".into(), "env (LAOSHI_MODEL)".into())));
+        assert!(
+            sources.contains(&("api_base".into(), "global (global.toml)".into()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_layers_env_typed_values() -> Result<()> {
+        // -- Setup & Fixtures
+        let mut layers = ConfigLayers::default();
+        let fx_vars = fx_env(&[
+            ("LAOSHI_NAME", "123"),
+            ("LAOSHI_MODEL", "2024"),
+            ("LAOSHI_TEMPERATURE", "0.5"),
+            ("LAOSHI_INSTRUCTIONS_FILES", r#"["a.md", "b.md"]"#),
+            ("LAOSHI_METADATA__TEAM", "true"),
+            ("LAOSHI_THEME__ASCII", "true"),
+            ("LAOSHI_THEME__COLORS__ACCENT", "45"),
+            ("LAOSHI_THEME__COLORS__ERROR", "red"),
+            ("LAOSHI_TUTOR__HSK_LEVEL", "3"),
+            ("LAOSHI_DEBUG", "1"),
+            ("OTHER_NAME", "other"),
+        ]);
+
+        // -- Exec
+        layers.merge_env(fx_vars)?;
+
+        // -- Check
+        let table = layers.to_table();
+        assert_eq!(table["name"], Value::String("123".into()));
+        assert_eq!(table["model"], Value::String("2024".into()));
+        assert_eq!(table["temperature"], Value::Float(0.5));
+        assert_eq!(
+            table["instructions_files"],
+            Value::Array(vec!["a.md".into(), "b.md".into()])
+        );
+        assert_eq!(table["metadata"]["team"], Value::String("true".into()));
+        assert_eq!(table["theme"]["ascii"], Value::Boolean(true));
+        assert_eq!(table["theme"]["colors"]["accent"], Value::Integer(45));
+        assert_eq!(
+            table["theme"]["colors"]["error"],
+            Value::String("red".into())
+        );
+        assert_eq!(table["tutor"]["hsk_level"], Value::Integer(3));
+        // unknown keys are not merged
+        assert!(!table.contains_key("debug"));
+        assert_eq!(table.len(), 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_layers_env_invalid_value() -> Result<()> {
+        // -- Setup & Fixtures
+        let mut layers = ConfigLayers::default();
+
+        // -- Exec
+        let res = layers.merge_env(fx_env(&[("LAOSHI_TOP_P", "high")]));
+
+        // -- Check
+        let Err(Error::ConfigEnvValue { var, .. }) = res else {
+            return Err(format!("expected ConfigEnvValue, got {res:?}").into());
+        };
+        assert_eq!(var, "LAOSHI_TOP_P");
+        let res = layers.merge_env(fx_env(&[("LAOSHI_TUTOR__PINYIN", "above")]));
+        let Err(Error::ConfigEnvValue { var, expected }) = res else {
+            return Err(format!("expected ConfigEnvValue, got {res:?}").into());
+        };
+        assert_eq!(var, "LAOSHI_TUTOR__PINYIN");
+        assert_eq!(expected, "one of: off, ruby, inline");
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
@@ -13,28 +13,61 @@
 
 // region:       -- Modules
 
+mod bundles;
 mod config;
-
-use crate::ais::assistant::{self, load_or_create_assistant, upload_file_by_name};
-use crate::ais::{new_openai_client, AssistantId, ThreadId};
-use crate::laoshi::config::Config;
+mod grammar;
+mod instructions;
+mod layers;
+mod plan;
+mod profile;
+mod quiz;
+mod reader;
+mod status;
+mod theme;
+mod tutor;
+mod vocab;
+
+pub use bundles::BundleState;
+pub use grammar::{
+    CategoryStats, Correction, ErrorCategory, GrammarEntry, GrammarError,
+    GrammarLog, RecurringError,
+};
+pub use layers::{ConfigEntry, ConfigLayers, ConfigSource};
+pub use plan::{Change, Plan, UploadReason};
+pub use profile::{LearnerProfile, PROFILE_FIELDS};
+pub use quiz::{
+    Quiz, QuizGrade, QuizQuestion, QuizResult, QuizScores, TopicStats,
+    MAX_QUIZ_QUESTIONS,
+};
+pub use reader::{Reader, ReaderCheck, MAX_READER_REWRITES, MAX_UNKNOWN_SHARE};
+pub use status::{BundleStatus, Status};
+pub use theme::{ThemeColor, ThemeColors, ThemeConfig, ThemeIcons};
+pub use tutor::{PinyinDisplay, TutorConfig};
+pub use vocab::{Card, Deck, ImportSummary, ReviewLog};
+
+use crate::ais::assistant::{self, load_or_create_assistant, RunUsage};
+use crate::ais::{AssistantId, FileId, ThreadId};
+use crate::laoshi::bundles::{bundle_file_name, BundleHashes};
+use crate::laoshi::config::{Config, FileBundle};
 use crate::utils::files::bundle_to_file;
+use crate::utils::hash::sha256_hex;
 use crate::{Error, Result};
 
 use async_openai::{config::OpenAIConfig, Client};
-use derive_more::{Deref, From};
+use derive_more::Deref;
 use serde::{Deserialize, Serialize};
-use simple_fs::{
-    ensure_dir, list_files, load_toml, read_to_string, save_json, SPath,
-};
+use simple_fs::{ensure_dir, list_files, save_json, ListOptions, SPath};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 // endregion:    -- Modules
 // NOTE: ! EVERYTHING file system related depends on where
 // this TOML file is located locally and its configuration!
 // This affects the Laoshi.dir PathBuf and uploads/deletions,etc.
 const LAOSHI_TOML: &str = "laoshi.toml";
+/// The current conversation, in the data dir (also read by `Status`)
+const CONV_JSON: &str = "conv.json";
 
 // NOTE: TIP! When new to Rust and making structs, 90% of time
 // make sure to OWN the data! E.g., PathBuf (owned) instead of Path (ref).
@@ -45,6 +78,13 @@ pub struct Laoshi {
     oac: Client<OpenAIConfig>,
     assistant_id: AssistantId,
     config: Config,
+    /// No progress/status output on stdout (see `set_quiet`)
+    quiet: bool,
+    /// The tokens used by the chats of this session
+    // NOTE: Mutex since `chat` takes `&self` (e.g., Arc<Laoshi> in the TUI)
+    usage: Mutex<RunUsage>,
+    /// Sent with each run as additional instructions
+    profile: LearnerProfile,
 }
 
 // NOTE: TIP! It's better to wrap types (eg. String) with our custom types,
@@ -54,11 +94,45 @@ pub struct Laoshi {
 // NOTE: We add both Serialize/Deserialize since we'll store the
 // conversation in a laoshi.json file, so we don't have to create
 // a new ThreadId each time we run/use cargo watch.
-#[derive(Debug, From, Deref, Serialize, Deserialize)]
+#[derive(Debug, Deref, Serialize, Deserialize)]
 pub struct Conversation {
+    #[deref]
     thread_id: ThreadId,
+    /// Uploaded with `/attach`, sent with the next user message
+    #[serde(default)]
+    pending_attachments: Vec<Attachment>,
+    /// All the files uploaded for this conversation (deleted with it)
+    #[serde(default)]
+    uploaded_files: Vec<Attachment>,
+}
+
+/// A local file uploaded to be attached to a user message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub file_name: String,
+    file_id: String,
+}
+
+impl From<ThreadId> for Conversation {
+    fn from(thread_id: ThreadId) -> Self {
+        Self {
+            thread_id,
+            pending_attachments: Vec::new(),
+            uploaded_files: Vec::new(),
+        }
+    }
 }
 
+impl Conversation {
+    /// The attachments of the next user message.
+    pub fn pending_attachments(&self) -> &[Attachment] {
+        &self.pending_attachments
+    }
+}
+
+/// The OpenAI limit of files per message.
+const MAX_MESSAGE_FILES: usize = 10;
+
 impl Laoshi {
     // -- Constructor functions
     // NOTE: This is where we use all our helpers with assistants, threads, ixs, etc.
@@ -67,14 +141,35 @@ impl Laoshi {
     pub async fn init_from_dir(
         dir: impl AsRef<Path>,
         recreate_assistant: bool, // For assistant::load_or_create_assistant()
+    ) -> Result<Self> {
+        let laoshi = Self::load_from_dir(dir, recreate_assistant).await?;
+
+        // -- Upload instructions
+        laoshi.upload_instructions().await?;
+
+        // -- Upload files
+        // NOTE: Not forcing an upload, since we will upload the bundle file
+        // if it's not present.
+        laoshi.upload_files(false).await?;
+
+        Ok(laoshi)
+    }
+
+    /// Loads (or creates) the assistant of the laoshi `dir`, without
+    /// uploading the instructions or the files (see `init_from_dir`).
+    pub async fn load_from_dir(
+        dir: impl AsRef<Path>,
+        recreate_assistant: bool,
     ) -> Result<Self> {
         let dir = dir.as_ref(); // DEFAULT_DIR = "laoshi"
 
         // -- Load from the directory
-        let config: Config = load_toml(dir.join(LAOSHI_TOML))?; // laoshi/laoshi.toml
+        // NOTE: laoshi/laoshi.toml, merged with the global config and env vars
+        let config = Config::load(dir, LAOSHI_TOML)?;
+        config.validate()?;
 
         // -- Get or create our OAI Assistant
-        let oac = new_openai_client()?;
+        let oac = config.new_openai_client()?;
         let assistant_id = load_or_create_assistant(
             &oac,
             // Q: Why does &config.into() convert into '&_'
@@ -84,35 +179,63 @@ impl Laoshi {
         )
         .await?;
 
+        // -- Load the learner profile
+        // NOTE: A malformed profile.toml is reported, not fatal (default profile)
+        let profile = LearnerProfile::load_from_dir(dir).unwrap_or_else(|err| {
+            eprintln!(
+                "X Can't load the profile, using the default\n    cause: {err}"
+            );
+            LearnerProfile::default()
+        });
+
         // -- Create the Laoshi agent
         let laoshi = Laoshi {
             dir: dir.to_path_buf(),
             oac,
             assistant_id,
             config,
+            quiet: false,
+            usage: Mutex::default(),
+            profile,
         };
 
-        // -- Upload instructions
-        laoshi.upload_instructions().await?;
-
-        // -- Upload files
-        // NOTE: Not forcing an upload, since we will upload the bundle file
-        // if it's not present.
-        laoshi.upload_files(false).await?;
-
         Ok(laoshi)
     }
 
     // -- Public functions
+    /// Turns off the progress/status output of the chat and conversation
+    /// functions on stdout (e.g., for a full-screen UI).
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
     pub fn name(&self) -> &str {
         &self.config.name
     }
 
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// The tokens used by the chats since this laoshi was created.
+    pub fn session_usage(&self) -> RunUsage {
+        self.usage.lock().map(|usage| *usage).unwrap_or_default()
+    }
+
+    pub fn profile(&self) -> &LearnerProfile {
+        &self.profile
+    }
+
+    /// Sets (and saves) the learner profile, used from the next run.
+    pub fn set_profile(&mut self, profile: LearnerProfile) -> Result<()> {
+        profile.save_to_dir(&self.dir)?;
+        self.profile = profile;
+        Ok(())
+    }
+
     pub async fn upload_instructions(&self) -> Result<bool> {
-        let file = &self.dir.join(&self.config.instructions_file);
-        if file.exists() {
+        if let Some(ix_content) = self.compose_instructions()? {
             // -- Upload ix and return 'true'
-            let ix_content = read_to_string(file)?;
             // Q: How to convert Result<()> into Result<bool>?
             assistant::upload_instructions(
                 &self.oac,
@@ -128,6 +251,41 @@ impl Laoshi {
         }
     }
 
+    /// Returns the composed instructions (see `compose_instructions_from_dir`).
+    pub fn compose_instructions(&self) -> Result<Option<String>> {
+        instructions::compose(&self.dir, &self.config)
+    }
+
+    /// Composes the instructions of the laoshi `dir` without loading the
+    /// assistant (no OpenAI calls).
+    ///
+    /// The instructions files (`instructions_file` then `instructions_files`)
+    /// are rendered as templates and joined. Returns `None` if none are
+    /// configured, and fails if one is missing.
+    pub fn compose_instructions_from_dir(
+        dir: impl AsRef<Path>,
+    ) -> Result<Option<String>> {
+        let dir = dir.as_ref();
+        let config = Config::load(dir, LAOSHI_TOML)?;
+        instructions::compose(dir, &config)
+    }
+
+    /// The merged config layers of the laoshi `dir` (global, project, env),
+    /// with the source of each value (no OpenAI calls).
+    pub fn config_layers_from_dir(dir: impl AsRef<Path>) -> Result<ConfigLayers> {
+        ConfigLayers::load(dir.as_ref(), LAOSHI_TOML)
+    }
+
+    /// The [theme] of the laoshi `dir` config, for the UIs (no OpenAI calls).
+    pub fn theme_config_from_dir(dir: impl AsRef<Path>) -> Result<ThemeConfig> {
+        theme::load(dir.as_ref(), LAOSHI_TOML)
+    }
+
+    /// The [tutor] of the laoshi `dir` config, for the UIs (no OpenAI calls).
+    pub fn tutor_config_from_dir(dir: impl AsRef<Path>) -> Result<TutorConfig> {
+        tutor::load(dir.as_ref(), LAOSHI_TOML)
+    }
+
     // NOTE: Conversations will be serialized and stored in a conv.json
     // file within the data_dir (agent/.agent/conv.json). This way
     // we can persist the conversation in a way between sessions.
@@ -135,12 +293,20 @@ impl Laoshi {
         &self,
         recreate: bool,
     ) -> Result<Conversation> {
-        let conversation_file = self.data_dir()?.join("conv.json");
+        let conversation_file = self.conversation_file()?;
 
         // -- Delete if recreate and exists
         if recreate && conversation_file.exists() {
+            // NOTE: The attached files only live as long as their conversation
+            if let Ok(conversation) =
+                simple_fs::load_json::<Conversation>(&conversation_file)
+            {
+                self.delete_uploaded_files(&conversation).await;
+            }
             fs::remove_file(&conversation_file)?;
-            println!("Conversation file deleted. Creating new file...");
+            if !self.quiet {
+                println!("Conversation file deleted. Creating new file...");
+            }
         }
 
         // -- Previous conversation exists, let's load
@@ -160,12 +326,16 @@ impl Laoshi {
                     Error::CannotFindThreadIdForConv(conversation.to_string())
                 })?;
             // println!("{} conversation loaded", icon_check());
-            println!("Conversation loaded");
+            if !self.quiet {
+                println!("Conversation loaded");
+            }
             conversation
         } else {
             // -- No prior Conversation or conv.json file found; Create new Conversation
             let thread_id = assistant::create_thread(&self.oac).await?;
-            println!("Conversation created");
+            if !self.quiet {
+                println!("Conversation created");
+            }
             // Convert ThreadId into a Conversation struct
             // Q: How does this work/convert? Deref? From trait?
             let conversation = thread_id.into();
@@ -177,41 +347,76 @@ impl Laoshi {
         Ok(conversation)
     }
 
-    pub async fn chat(&self, conv: &Conversation, msg: &str) -> Result<String> {
+    pub async fn chat(&self, conv: &mut Conversation, msg: &str) -> Result<String> {
         // Q: What's the mental model here? We return the model response in String?
         // A: That's exactly what our assistant::run_thread_msg() does!
         // NOTE: Assistants don't know about our custom Conversation, only ThreadId
-        let res = assistant::run_thread_msg(
+        let file_ids: Vec<FileId> = conv
+            .pending_attachments
+            .iter()
+            .map(|a| FileId::from(a.file_id.clone()))
+            .collect();
+        let (res, usage) = assistant::run_thread_msg(
             &self.oac,
             &self.assistant_id,
             &conv.thread_id,
             msg,
+            &file_ids,
+            self.profile.additional_instructions().as_deref(),
+            !self.quiet,
         )
         .await?;
+        if let (Some(usage), Ok(mut session_usage)) = (usage, self.usage.lock()) {
+            session_usage.add(&usage);
+        }
+
+        // -- The attachments were sent (kept if the run failed)
+        if !conv.pending_attachments.is_empty() {
+            conv.pending_attachments.clear();
+            save_json(self.conversation_file()?, &*conv)?;
+        }
 
         Ok(res)
     }
 
+    /// Uploads the `file` and attaches it to the next user message of `conv`.
+    pub async fn attach_file(
+        &self,
+        conv: &mut Conversation,
+        file: impl AsRef<Path>,
+    ) -> Result<Attachment> {
+        let file = file.as_ref();
+        if !file.is_file() {
+            return Err(Error::AttachmentNotFound(
+                file.to_string_lossy().to_string(),
+            ));
+        }
+        if conv.pending_attachments.len() >= MAX_MESSAGE_FILES {
+            return Err(Error::TooManyAttachments(MAX_MESSAGE_FILES));
+        }
+
+        let file_id = assistant::upload_message_file(&self.oac, file).await?;
+        let attachment = Attachment {
+            file_name: file
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            file_id: file_id.to_string(),
+        };
+
+        // NOTE: Saved right away, so the uploaded files can always be cleaned up.
+        conv.pending_attachments.push(attachment.clone());
+        conv.uploaded_files.push(attachment.clone());
+        save_json(self.conversation_file()?, &*conv)?;
+
+        Ok(attachment)
+    }
+
     pub async fn upload_files(&self, recreate: bool) -> Result<u32> {
         let mut num_uploaded = 0;
 
-        // -- Get the laoshi/files directory
-        let data_files_dir = self.data_files_dir()?; // laoshi/files directory
-
         // -- Clean out old/obsolete files from laoshi/files directory
-        let excluded_element = format!("*{}*", &self.assistant_id);
-        for file in list_files(
-            &data_files_dir,
-            Some(&["*.rs", "*.md"]),
-            Some(&[&excluded_element]),
-        )? {
-            // Safeguard
-            if !file.to_str().contains(".laoshi") {
-                return Err(Error::ShouldNotDeleteLocalFile(file.to_string()));
-            }
-            // Delete old file
-            fs::remove_file(&file)?;
-        }
+        self.clean_data_files()?;
 
         // -- Generate and upload the laoshi/files bundles
 
@@ -228,83 +433,194 @@ impl Laoshi {
         // Q: self.dir.join(&bundle.src_dir) ->> "laoshi/files", right?
         // A: self.dir ->> the dir of where laoshi.toml is stored ie "laoshi",
         // so, yes, self.dir.join(&bundle.src_dir) ->> "laoshi/files"
+        let mut bundle_hashes = BundleHashes::load(&self.data_dir()?);
         for bundle in self.config.file_bundles.iter() {
-            // Get the specific bundle's src_dir (e.g, "laoshi/files", "crates")
-            //
-            let src_dir = self.dir.join(&bundle.src_dir);
-
-            // Check that we have an existing dir
-            if src_dir.is_dir() {
-                // NOTE: Get our src_globs (e.g., ["**/*.rs] or ["*.md"]) as a Vec<&str>
-                // so we can pass as a slice of ref of String (&[&str]) needed for list_files() below.
-                let src_globs: Vec<&str> =
-                // Q: How to use map() to return &str from String? Any difference
-                // between these approaches?
-                    // bundle.src_globs.iter().map(|g| g.as_ref()).collect();
-                bundle.src_globs.iter().map(AsRef::as_ref).collect();
-                // bundle.src_globs.iter().map(|g| AsRef::as_ref(g)).collect();
-
-                let files = list_files(&src_dir, Some(&src_globs), None)?;
-
-                if !files.is_empty() {
-                    // Compute the bundle file name
-                    let bundle_file_name = format!(
-                        "{}-{}-bundle-{}.{}",
-                        self.name(),        // "laoshi-01"
-                        bundle.bundle_name, // "knowledge"
-                        self.assistant_id,  // "???"
-                        bundle.dst_ext,     // "md"
-                    );
-                    // Build full path file name: laoshi-01-knowledge-bundle-???.md
-                    let bundle_file = self.data_files_dir()?.join(bundle_file_name);
-                    // NOTE: Here bundle_file is an SPath because the file does not exist
-                    // (SFile construction does an is_file() check by contract)
-                    let bundle_file = SPath::try_from(bundle_file)?;
-                    // let bundle_file = SPath::from_path(bundle_file)?;
-
-                    // If the file doesn't exist, force a re-upload
-                    // NOTE: TIP! You can use the presence of a file as state sometimes
-                    let force_reupload = recreate || !bundle_file.path().exists();
-
-                    // Rebundle no matter if it already exists (while still developing)
-                    // Q: How to convert from PathBuf ->> SPath
-                    bundle_to_file(files, &bundle_file)?;
-
-                    // Upload and attach to Assistant
-                    let (_, has_uploaded) = assistant::upload_file_by_name(
-                        &self.oac,
-                        &self.assistant_id,
-                        &bundle_file,
-                        force_reupload,
-                    )
-                    .await?;
-
-                    // Update our total upload count
-                    if has_uploaded {
-                        num_uploaded += 1;
-                    }
-                }
+            let has_uploaded = self
+                .upload_bundle(bundle, recreate, &mut bundle_hashes)
+                .await?;
+
+            // Update our total upload count
+            if has_uploaded {
+                num_uploaded += 1;
             }
         }
         // -- Return u32 for number of files uploaded
         Ok(num_uploaded)
     }
 
+    /// Like `upload_files`, but only for the bundle named `bundle_name`.
+    /// Returns `true` if the bundle has been uploaded (false if no files).
+    pub async fn upload_files_of_bundle(
+        &self,
+        bundle_name: &str,
+        recreate: bool,
+    ) -> Result<bool> {
+        let bundle = self
+            .config
+            .file_bundles
+            .iter()
+            .find(|b| b.bundle_name == bundle_name)
+            .ok_or_else(|| Error::BundleNotFound(bundle_name.to_string()))?;
+
+        self.clean_data_files()?;
+        let mut bundle_hashes = BundleHashes::load(&self.data_dir()?);
+        self.upload_bundle(bundle, recreate, &mut bundle_hashes)
+            .await
+    }
+
+    /// The names of the config file bundles (e.g., "knowledge").
+    pub fn bundle_names(&self) -> Vec<&str> {
+        self.config
+            .file_bundles
+            .iter()
+            .map(|b| b.bundle_name.as_str())
+            .collect()
+    }
+
+    /// The upload state of the config file bundles, from the local bundle
+    /// hashes (no OpenAI calls, see `Plan` for the remote diff).
+    pub fn bundle_states(&self) -> Result<Vec<BundleState>> {
+        let bundle_hashes = BundleHashes::load(&self.data_dir()?);
+        self.config
+            .file_bundles
+            .iter()
+            .map(|bundle| {
+                let file_name =
+                    bundle_file_name(self.name(), bundle, &self.assistant_id);
+                Ok(BundleState {
+                    bundle_name: bundle.bundle_name.clone(),
+                    num_files: bundle.src_files(&self.dir)?.len(),
+                    uploaded: bundle_hashes.get(&file_name).is_some(),
+                })
+            })
+            .collect()
+    }
+
+    /// Bundles the `bundle` files and uploads the bundle file (if needed).
+    /// Returns `true` if the bundle has been uploaded (false if no files).
+    async fn upload_bundle(
+        &self,
+        bundle: &FileBundle,
+        recreate: bool,
+        bundle_hashes: &mut BundleHashes,
+    ) -> Result<bool> {
+        let files = bundle.src_files(&self.dir)?;
+        if files.is_empty() {
+            return Ok(false);
+        }
+
+        // Compute the bundle file name
+        let bundle_file_name =
+            bundle_file_name(self.name(), bundle, &self.assistant_id);
+        // Build full path file name: laoshi-01-knowledge-bundle-???.md
+        let bundle_file = self.data_files_dir()?.join(&bundle_file_name);
+        // NOTE: Here bundle_file is an SPath because the file does not exist
+        // (SFile construction does an is_file() check by contract)
+        let bundle_file = SPath::try_from(bundle_file)?;
+        // let bundle_file = SPath::from_path(bundle_file)?;
+
+        // If the file doesn't exist, force a re-upload
+        // NOTE: TIP! You can use the presence of a file as state sometimes
+        let force_reupload = recreate || !bundle_file.path().exists();
+
+        // Rebundle no matter if it already exists (while still developing)
+        // Q: How to convert from PathBuf ->> SPath
+        bundle_to_file(files, &bundle_file)?;
+
+        // Upload and attach to Assistant
+        let (_, has_uploaded) = assistant::upload_file_by_name(
+            &self.oac,
+            &self.assistant_id,
+            &bundle_file,
+            force_reupload,
+        )
+        .await?;
+
+        // Remember what we uploaded (see `Plan`)
+        if has_uploaded {
+            let bundle_hash = sha256_hex(fs::read(&bundle_file)?);
+            bundle_hashes.record(&bundle_file_name, bundle_hash)?;
+        }
+
+        Ok(has_uploaded)
+    }
+
     // -- Private functions
+    /// Deletes a one-off `conversation` (e.g., of a quiz), its thread and
+    /// uploaded files.
+    pub(super) async fn delete_conversation(&self, conversation: &Conversation) {
+        self.delete_uploaded_files(conversation).await;
+        assistant::delete_thread(&self.oac, &conversation.thread_id).await;
+    }
+
+    /// Deletes the remote files uploaded for the `conversation`.
+    async fn delete_uploaded_files(&self, conversation: &Conversation) {
+        for attachment in conversation.uploaded_files.iter() {
+            let file_id = FileId::from(attachment.file_id.clone());
+            assistant::delete_message_file(
+                &self.oac,
+                &file_id,
+                &attachment.file_name,
+            )
+            .await;
+        }
+    }
+
+    /// Deletes the local bundle files of other (older) assistants.
+    fn clean_data_files(&self) -> Result<()> {
+        // -- Get the laoshi/files directory
+        let data_files_dir = self.data_files_dir()?; // laoshi/files directory
+
+        let excluded_element = format!("*{}*", &self.assistant_id);
+        for file in list_files(
+            &data_files_dir,
+            Some(&["*.rs", "*.md"]),
+            Some(ListOptions::from(vec![excluded_element.as_str()])),
+        )? {
+            // Safeguard
+            if !file.to_str().contains(".laoshi") {
+                return Err(Error::ShouldNotDeleteLocalFile(file.to_string()));
+            }
+            // Delete old file
+            fs::remove_file(&file)?;
+        }
+
+        Ok(())
+    }
+
     /// Where we store conversations, data, bundles, instructions
-    fn data_dir(&self) -> Result<PathBuf> {
-        let data_dir = self.dir.join(".laoshi"); // laoshi/.laoshi
-        ensure_dir(&data_dir)?;
-        Ok(data_dir)
+    pub fn data_dir(&self) -> Result<PathBuf> {
+        data_dir(&self.dir)
+    }
+
+    /// The current conversation (laoshi/.laoshi/conv.json)
+    fn conversation_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join(CONV_JSON))
     }
 
     /// Where we store file bundles
     fn data_files_dir(&self) -> Result<PathBuf> {
-        // laoshi/file directory
-        let dir = self.data_dir()?.join("files");
-        // NOTE: TIP! We could write ensure_dir() helper here, but better
-        // is to create and use a utils module for these.
-        ensure_dir(&dir)?;
-        Ok(dir)
+        data_files_dir(&self.dir)
     }
 }
+
+// region:       -- Dir Helpers
+// NOTE: Free functions so they can be used before we have a Laoshi (e.g., plan).
+
+/// The laoshi data dir of the laoshi `dir` (laoshi/.laoshi)
+fn data_dir(dir: &Path) -> Result<PathBuf> {
+    let data_dir = dir.join(".laoshi"); // laoshi/.laoshi
+    ensure_dir(&data_dir)?;
+    Ok(data_dir)
+}
+
+/// The file bundles dir of the laoshi `dir` (laoshi/.laoshi/files)
+fn data_files_dir(dir: &Path) -> Result<PathBuf> {
+    let dir = data_dir(dir)?.join("files");
+    // NOTE: TIP! We could write ensure_dir() helper here, but better
+    // is to create and use a utils module for these.
+    ensure_dir(&dir)?;
+    Ok(dir)
+}
+
+// endregion:    -- Dir Helpers
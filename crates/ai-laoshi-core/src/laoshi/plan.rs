@@ -0,0 +1,316 @@
+// NOTE: A terraform-like plan/apply of the laoshi dir against the remote
+// assistant. The plan compares the local config, the composed instructions
+// and the bundle hashes with the remote assistant (model, tools, ixs, files),
+// and `Plan::apply` performs exactly these changes (nothing is recreated
+// blindly like `init_from_dir(.., true)` does).
+use crate::ais::assistant::{self, default_tools, tool_names, CreateConfig};
+use crate::ais::{AssistantId, FileId};
+use crate::laoshi::bundles::{bundle_file_name, BundleHashes};
+use crate::laoshi::config::Config;
+use crate::laoshi::{data_dir, instructions, Laoshi, LearnerProfile, LAOSHI_TOML};
+use crate::utils::files::bundle_to_string;
+use crate::utils::hash::sha256_hex;
+use crate::Result;
+use async_openai::types::{AssistantObject, ModifyAssistantRequest};
+use async_openai::{config::OpenAIConfig, Client};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+// region:       -- Types
+
+/// One change of a `Plan` (for display, `Plan::apply` performs them all).
+#[derive(Debug)]
+pub enum Change {
+    CreateAssistant {
+        name: String,
+        model: String,
+    },
+    UpdateAssistant {
+        field: &'static str,
+        from: String,
+        to: String,
+    },
+    UpdateInstructions {
+        from: String,
+        to: String,
+    },
+    UploadBundle {
+        bundle_name: String,
+        reason: UploadReason,
+    },
+    DeleteFile {
+        file_name: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum UploadReason {
+    /// Not uploaded yet
+    New,
+    /// Local content changed since the last upload
+    Changed,
+    /// Uploaded, but we do not have the hash of what was uploaded
+    Untracked,
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    dir: PathBuf,
+    config: Config,
+    oac: Client<OpenAIConfig>,
+    /// `None` when the assistant needs to be created
+    assistant_id: Option<AssistantId>,
+    modify_request: ModifyAssistantRequest,
+    /// The `config.file_bundles` indexes to upload
+    bundle_uploads: Vec<usize>,
+    obsolete_files: Vec<(String, FileId)>,
+    changes: Vec<Change>,
+}
+
+// endregion:    -- Types
+
+impl Plan {
+    /// Computes the plan of the laoshi `dir` (only reads the remote state).
+    pub async fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+
+        // -- Load the local state
+        let config = Config::load(dir, LAOSHI_TOML)?;
+        config.validate()?;
+        let ix_content = instructions::compose(dir, &config)?;
+        let bundle_hashes = BundleHashes::load(&data_dir(dir)?);
+
+        // -- Load the remote state
+        let oac = config.new_openai_client()?;
+        let assistant_obj = assistant::first_by_name(&oac, &config.name).await?;
+
+        let mut plan = Plan {
+            dir: dir.to_path_buf(),
+            oac,
+            assistant_id: None,
+            modify_request: ModifyAssistantRequest::default(),
+            bundle_uploads: Vec::new(),
+            obsolete_files: Vec::new(),
+            changes: Vec::new(),
+            config,
+        };
+
+        let remote_files = match assistant_obj {
+            Some(assistant_obj) => {
+                plan.diff_assistant(&assistant_obj);
+                let assistant_id = AssistantId::from(assistant_obj.id);
+                let remote_files =
+                    assistant::get_files_hashmap(&plan.oac, &assistant_id).await?;
+                plan.assistant_id = Some(assistant_id);
+                plan.diff_instructions(assistant_obj.instructions, ix_content);
+                remote_files
+            }
+            None => {
+                plan.changes.push(Change::CreateAssistant {
+                    name: plan.config.name.clone(),
+                    model: plan.config.model.clone(),
+                });
+                plan.diff_instructions(None, ix_content);
+                HashMap::new()
+            }
+        };
+        plan.diff_bundles(remote_files, &bundle_hashes)?;
+
+        Ok(plan)
+    }
+
+    // -- Public functions
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Performs the plan changes and returns the ready laoshi.
+    pub async fn apply(self) -> Result<Laoshi> {
+        let Plan {
+            dir,
+            config,
+            oac,
+            assistant_id,
+            modify_request,
+            bundle_uploads,
+            obsolete_files,
+            ..
+        } = self;
+
+        // -- Create the assistant (if needed)
+        let assistant_id = match assistant_id {
+            Some(assistant_id) => assistant_id,
+            None => {
+                let assistant_id = assistant::create(&oac, (&config).into()).await?;
+                println!("Assistant {} created.", config.name);
+                assistant_id
+            }
+        };
+
+        // -- Update the assistant fields and instructions (one request)
+        if modify_request != ModifyAssistantRequest::default() {
+            oac.assistants()
+                .update(&assistant_id, modify_request)
+                .await?;
+            println!("Assistant {} updated.", config.name);
+        }
+
+        // -- Delete the files not part of the bundles anymore
+        for (file_name, file_id) in obsolete_files.iter() {
+            assistant::delete_file(&oac, &assistant_id, file_id, file_name).await;
+            println!("File deleted - {file_name}");
+        }
+
+        // NOTE: The profile is only sent with the chat runs, not loaded here
+        let laoshi = Laoshi {
+            dir,
+            oac,
+            assistant_id,
+            config,
+            quiet: false,
+            usage: Default::default(),
+            profile: LearnerProfile::default(),
+        };
+
+        // -- Upload the new/changed bundles
+        laoshi.clean_data_files()?;
+        let mut bundle_hashes = BundleHashes::load(&laoshi.data_dir()?);
+        for bundle_idx in bundle_uploads {
+            let bundle = &laoshi.config.file_bundles[bundle_idx];
+            laoshi
+                .upload_bundle(bundle, true, &mut bundle_hashes)
+                .await?;
+        }
+
+        Ok(laoshi)
+    }
+
+    // -- Private functions
+    fn diff_assistant(&mut self, assistant_obj: &AssistantObject) {
+        // -- Config fields (model, description, metadata)
+        let create_config = CreateConfig::from(&self.config);
+        let (modify_request, drifted) =
+            assistant::config_drift(assistant_obj, &create_config);
+        for field in drifted {
+            let (from, to) = match field {
+                "model" => (
+                    assistant_obj.model.clone(),
+                    modify_request.model.clone().unwrap_or_default(),
+                ),
+                "description" => (
+                    assistant_obj.description.clone().unwrap_or_default(),
+                    modify_request.description.clone().unwrap_or_default(),
+                ),
+                _ => (
+                    fmt_metadata(assistant_obj.metadata.as_ref()),
+                    fmt_metadata(modify_request.metadata.as_ref()),
+                ),
+            };
+            self.changes
+                .push(Change::UpdateAssistant { field, from, to });
+        }
+        self.modify_request = modify_request;
+
+        // -- Tools
+        let tools = default_tools();
+        let (remote_names, names) =
+            (tool_names(&assistant_obj.tools), tool_names(&tools));
+        if remote_names != names {
+            self.changes.push(Change::UpdateAssistant {
+                field: "tools",
+                from: format!("[{}]", remote_names.join(", ")),
+                to: format!("[{}]", names.join(", ")),
+            });
+            self.modify_request.tools = Some(tools);
+        }
+    }
+
+    fn diff_instructions(&mut self, remote: Option<String>, local: Option<String>) {
+        // NOTE: No local instructions means unmanaged (like `upload_instructions`)
+        let Some(local) = local else {
+            return;
+        };
+        let remote = remote.unwrap_or_default();
+        if remote != local {
+            self.changes.push(Change::UpdateInstructions {
+                from: remote,
+                to: local.clone(),
+            });
+            self.modify_request.instructions = Some(local);
+        }
+    }
+
+    fn diff_bundles(
+        &mut self,
+        mut remote_files: HashMap<String, FileId>,
+        bundle_hashes: &BundleHashes,
+    ) -> Result<()> {
+        for (bundle_idx, bundle) in self.config.file_bundles.iter().enumerate() {
+            let files = bundle.src_files(&self.dir)?;
+            if files.is_empty() {
+                continue;
+            }
+
+            let reason = match self.assistant_id.as_ref() {
+                // New assistant, so new bundle file (name has the assistant id)
+                None => Some(UploadReason::New),
+                Some(assistant_id) => {
+                    let file_name =
+                        bundle_file_name(&self.config.name, bundle, assistant_id);
+                    let hash = sha256_hex(bundle_to_string(&files)?);
+                    match (
+                        remote_files.remove(&file_name),
+                        bundle_hashes.get(&file_name),
+                    ) {
+                        (None, _) => Some(UploadReason::New),
+                        (Some(_), None) => Some(UploadReason::Untracked),
+                        (Some(_), Some(uploaded_hash)) if uploaded_hash != hash => {
+                            Some(UploadReason::Changed)
+                        }
+                        (Some(_), Some(_)) => None,
+                    }
+                }
+            };
+
+            if let Some(reason) = reason {
+                self.changes.push(Change::UploadBundle {
+                    bundle_name: bundle.bundle_name.clone(),
+                    reason,
+                });
+                self.bundle_uploads.push(bundle_idx);
+            }
+        }
+
+        // -- The remaining remote files are not part of any bundle anymore
+        let mut obsolete_files: Vec<(String, FileId)> =
+            remote_files.into_iter().collect();
+        obsolete_files.sort_by(|a, b| a.0.cmp(&b.0));
+        for (file_name, _) in obsolete_files.iter() {
+            self.changes.push(Change::DeleteFile {
+                file_name: file_name.clone(),
+            });
+        }
+        self.obsolete_files = obsolete_files;
+
+        Ok(())
+    }
+}
+
+// region:       -- Support
+
+fn fmt_metadata(metadata: Option<&HashMap<String, Value>>) -> String {
+    // NOTE: BTreeMap so the keys are sorted (stable display)
+    let metadata: BTreeMap<_, _> = metadata.into_iter().flatten().collect();
+    serde_json::to_string(&metadata).unwrap_or_default()
+}
+
+// endregion:    -- Support
@@ -0,0 +1,188 @@
+// NOTE: The learner profile: a personal (not shared) file of the learner
+// level, goals, native language, script and topics to avoid, sent with each
+// run as additional instructions, so the shared assistant and its
+// instructions stay the same for the whole team.
+//
+// The profile level is also the learner level of the tutor (/level, the
+// rewrites, the auto-save and /reader) when it is an HSK level (e.g.,
+// "HSK 3"), over the [tutor] hsk_level (the default if no profile level).
+//
+// Kept in laoshi/.laoshi/profile.toml, e.g.,
+// level = "HSK 3"
+// goals = "Business meetings, reading the news"
+// native_language = "French"
+// script = "traditional"
+// avoid_topics = ["politics"]
+use crate::chinese::Script;
+use crate::laoshi::data_dir;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const PROFILE_TOML: &str = "profile.toml";
+
+/// The fields of the profile (for `LearnerProfile::set`).
+pub const PROFILE_FIELDS: &[&str] = &[
+    "level",
+    "goals",
+    "native_language",
+    "script",
+    "avoid_topics",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearnerProfile {
+    /// e.g., "HSK 3", "beginner"
+    pub level: Option<String>,
+    pub goals: Option<String>,
+    pub native_language: Option<String>,
+    pub script: Option<Script>,
+    pub avoid_topics: Vec<String>,
+}
+
+impl LearnerProfile {
+    /// Loads the profile of the laoshi `dir` (default if none yet).
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let file = data_dir(dir.as_ref())?.join(PROFILE_TOML);
+        match file.is_file() {
+            true => Self::from_toml(&fs::read_to_string(&file)?),
+            false => Ok(Self::default()),
+        }
+    }
+
+    /// Saves the profile in the laoshi `dir`.
+    pub fn save_to_dir(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let file = data_dir(dir.as_ref())?.join(PROFILE_TOML);
+        fs::write(file, self.to_toml())?;
+        Ok(())
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|cause| Error::ConfigToml {
+            file: PROFILE_TOML.to_string(),
+            cause,
+        })
+    }
+
+    pub fn to_toml(&self) -> String {
+        // NOTE: Only plain values, so it can't fail
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The HSK level of the `level` (e.g., "HSK 3", "hsk3" or "3"), `None`
+    /// if not an HSK level (e.g., "beginner").
+    pub fn hsk_level(&self) -> Option<u8> {
+        let level = self.level.as_deref()?.trim().to_lowercase();
+        let digits: String = level
+            .strip_prefix("hsk")
+            .unwrap_or(&level)
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok().filter(|level| (1..=9).contains(level))
+    }
+
+    /// Sets the `field` (see `PROFILE_FIELDS`) to the `value`, an empty
+    /// value clears it. The `avoid_topics` are comma separated.
+    pub fn set(&mut self, field: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        let text = (!value.is_empty()).then(|| value.to_string());
+        match field {
+            "level" => self.level = text,
+            "goals" => self.goals = text,
+            "native_language" => self.native_language = text,
+            "script" if value.is_empty() => self.script = None,
+            "script" => {
+                let script = Script::from_name(value)
+                    .ok_or_else(|| Error::ProfileValue(value.to_string()))?;
+                self.script = Some(script);
+            }
+            "avoid_topics" => {
+                self.avoid_topics = value
+                    .split(',')
+                    .map(|topic| topic.trim().to_string())
+                    .filter(|topic| !topic.is_empty())
+                    .collect();
+            }
+            _ => return Err(Error::ProfileField(field.to_string())),
+        }
+        Ok(())
+    }
+
+    /// The additional instructions of the runs (`None` if empty profile).
+    pub fn additional_instructions(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut lines = vec![
+            "About the learner you are talking to (adapt your answers to it):"
+                .to_string(),
+        ];
+        if let Some(level) = &self.level {
+            lines.push(format!("- Chinese level: {level}"));
+        }
+        if let Some(goals) = &self.goals {
+            lines.push(format!("- Learning goals: {goals}"));
+        }
+        if let Some(native_language) = &self.native_language {
+            lines.push(format!(
+                "- Native language: {native_language} (use it for the explanations)"
+            ));
+        }
+        if let Some(script) = &self.script {
+            lines.push(format!(
+                "- Write the Chinese in {} characters",
+                script.name()
+            ));
+        }
+        if !self.avoid_topics.is_empty() {
+            lines.push(format!(
+                "- Topics to avoid: {}",
+                self.avoid_topics.join(", ")
+            ));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    #[test]
+    fn test_profile_hsk_level() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_levels = [
+            ("HSK 3", Some(3)),
+            ("hsk4", Some(4)),
+            ("5", Some(5)),
+            ("HSK 2 (reading)", Some(2)),
+            ("beginner", None),
+            ("HSK 0", None),
+        ];
+
+        // -- Exec & Check
+        for (fx_level, expected) in fx_levels {
+            let mut profile = LearnerProfile::default();
+            profile.set("level", fx_level)?;
+            assert_eq!(profile.hsk_level(), expected, "level: {fx_level}");
+        }
+        assert_eq!(LearnerProfile::default().hsk_level(), None);
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
@@ -0,0 +1,262 @@
+// NOTE: The quiz mode: the laoshi writes questions grounded in the file
+// bundles (e.g., the best practices of the `knowledge` bundle), then grades
+// the free-text answers and explains the mistakes. Each quiz has its own
+// thread, so it does not pollute the conversation (deleted by `end_quiz`).
+//
+// The scores are kept per topic in laoshi/.laoshi/quiz.json (see
+// `QuizScores::stats`, shown by `laoshi quiz stats`).
+use crate::ais::assistant;
+use crate::laoshi::bundles::bundle_file_name;
+use crate::laoshi::{data_dir, Conversation, Laoshi};
+use crate::utils::json::extract_json;
+use crate::{Error, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use simple_fs::{load_json, save_json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const QUIZ_JSON: &str = "quiz.json";
+
+pub const MAX_QUIZ_QUESTIONS: usize = 20;
+
+/// The stats topic of the quizzes without a topic.
+const ALL_TOPICS: &str = "(all)";
+
+// region:       -- Types
+
+#[derive(Debug)]
+pub struct Quiz {
+    pub topic: Option<String>,
+    pub questions: Vec<QuizQuestion>,
+    conversation: Conversation,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuizQuestion {
+    pub question: String,
+    /// The expected answer
+    pub answer: String,
+    /// Where the answer is in the files (e.g., a section title)
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuizGrade {
+    pub correct: bool,
+    /// From 0.0 to 1.0 (partial credit)
+    pub score: f32,
+    pub explanation: String,
+}
+
+#[derive(Debug, Default)]
+pub struct QuizScores {
+    file: PathBuf,
+    results: Vec<QuizResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizResult {
+    pub topic: String,
+    pub date: DateTime<Local>,
+    pub num_questions: usize,
+    /// The sum of the question scores
+    pub score: f32,
+}
+
+/// The progress on a topic (percents of the max score).
+#[derive(Debug)]
+pub struct TopicStats {
+    pub topic: String,
+    pub attempts: usize,
+    pub last_percent: f32,
+    pub best_percent: f32,
+    pub average_percent: f32,
+    pub last_date: DateTime<Local>,
+}
+
+#[derive(Deserialize)]
+struct QuizQuestions {
+    questions: Vec<QuizQuestion>,
+}
+
+// endregion:    -- Types
+
+impl Laoshi {
+    /// A new quiz of `num_questions` on the `topic` (all the bundles if none).
+    pub async fn new_quiz(
+        &self,
+        topic: Option<&str>,
+        num_questions: usize,
+    ) -> Result<Quiz> {
+        let num_questions = num_questions.clamp(1, MAX_QUIZ_QUESTIONS);
+        let files: Vec<String> = self
+            .config
+            .file_bundles
+            .iter()
+            .map(|bundle| {
+                bundle_file_name(&self.config.name, bundle, &self.assistant_id)
+            })
+            .collect();
+        let topic_text = match topic {
+            Some(topic) => format!("\"{topic}\""),
+            None => "the main topics of the files".to_string(),
+        };
+
+        let mut conversation: Conversation =
+            assistant::create_thread(&self.oac).await?.into();
+        let msg = format!(
+            "Quiz me on {topic_text}, from your files ({}).\n\
+             Write {num_questions} varied questions grounded ONLY in these files, \
+             each answerable in one or two sentences.\n\
+             Respond ONLY with a JSON object, no other text:\n\
+             {{\"questions\": [{{\"question\": \"...\", \"answer\": \"the expected answer\", \
+             \"source\": \"the file section\"}}]}}",
+            files.join(", ")
+        );
+        let questions = match self.chat(&mut conversation, &msg).await {
+            Ok(res) => quiz_questions(res, num_questions),
+            Err(err) => Err(err),
+        };
+        // NOTE: No quiz, no thread left behind
+        let questions = match questions {
+            Ok(questions) => questions,
+            Err(err) => {
+                self.delete_conversation(&conversation).await;
+                return Err(err);
+            }
+        };
+
+        Ok(Quiz {
+            topic: topic.map(str::to_string),
+            questions,
+            conversation,
+        })
+    }
+
+    /// Grades the `answer` to the question `idx` of the `quiz`.
+    pub async fn grade_quiz_answer(
+        &self,
+        quiz: &mut Quiz,
+        idx: usize,
+        answer: &str,
+    ) -> Result<QuizGrade> {
+        let question = &quiz.questions[idx];
+        let msg = format!(
+            "Grade my answer to question {}.\n\
+             Question: {}\nExpected answer: {}\nMy answer: {answer}\n\
+             It is a free-text answer: the meaning matters, not the wording.\n\
+             Respond ONLY with a JSON object, no other text:\n\
+             {{\"correct\": true or false, \"score\": 0.0 to 1.0, \
+             \"explanation\": \"why, with the right answer from the files if I am wrong\"}}",
+            idx + 1,
+            question.question,
+            question.answer,
+        );
+        let res = self.chat(&mut quiz.conversation, &msg).await?;
+
+        let mut grade: QuizGrade =
+            extract_json(&res).ok_or(Error::QuizInvalidJson(res))?;
+        grade.score = grade.score.clamp(0.0, 1.0);
+        Ok(grade)
+    }
+
+    /// Ends the `quiz` (deletes its thread).
+    pub async fn end_quiz(&self, quiz: Quiz) {
+        self.delete_conversation(&quiz.conversation).await;
+    }
+}
+
+impl Quiz {
+    /// The result of the quiz for the `scores` of its questions.
+    pub fn result(&self, scores: &[f32]) -> QuizResult {
+        QuizResult {
+            topic: topic_key(self.topic.as_deref()),
+            date: Local::now(),
+            num_questions: self.questions.len(),
+            score: scores.iter().sum(),
+        }
+    }
+}
+
+impl QuizResult {
+    pub fn percent(&self) -> f32 {
+        match self.num_questions {
+            0 => 0.0,
+            n => self.score / n as f32 * 100.0,
+        }
+    }
+}
+
+impl QuizScores {
+    /// Loads the scores of the laoshi `dir` (empty if none yet).
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let file = data_dir(dir.as_ref())?.join(QUIZ_JSON);
+        let results = match file.is_file() {
+            true => load_json(&file)?,
+            false => Vec::new(),
+        };
+        Ok(Self { file, results })
+    }
+
+    /// Adds the `result` and saves the scores file.
+    pub fn record(&mut self, result: QuizResult) -> Result<()> {
+        self.results.push(result);
+        save_json(&self.file, &self.results)?;
+        Ok(())
+    }
+
+    pub fn results(&self) -> &[QuizResult] {
+        &self.results
+    }
+
+    /// The stats by topic (sorted by topic).
+    pub fn stats(&self) -> Vec<TopicStats> {
+        let mut by_topic: BTreeMap<&str, Vec<&QuizResult>> = BTreeMap::new();
+        for result in &self.results {
+            by_topic.entry(&result.topic).or_default().push(result);
+        }
+
+        by_topic
+            .into_iter()
+            .filter_map(|(topic, results)| {
+                let last = results.iter().max_by_key(|r| r.date)?;
+                let percents: Vec<f32> =
+                    results.iter().map(|r| r.percent()).collect();
+                Some(TopicStats {
+                    topic: topic.to_string(),
+                    attempts: results.len(),
+                    last_percent: last.percent(),
+                    best_percent: percents.iter().copied().fold(0.0, f32::max),
+                    average_percent: percents.iter().sum::<f32>()
+                        / percents.len() as f32,
+                    last_date: last.date,
+                })
+            })
+            .collect()
+    }
+}
+
+// region:       -- Support
+
+/// The questions of the laoshi `res` (at most `num_questions`).
+fn quiz_questions(res: String, num_questions: usize) -> Result<Vec<QuizQuestion>> {
+    let QuizQuestions { mut questions } =
+        extract_json(&res).ok_or(Error::QuizInvalidJson(res))?;
+    questions.truncate(num_questions);
+    if questions.is_empty() {
+        return Err(Error::QuizNoQuestions);
+    }
+    Ok(questions)
+}
+
+/// e.g., " Error Handling " -> "error handling"
+fn topic_key(topic: Option<&str>) -> String {
+    match topic.map(|t| t.trim().to_lowercase()) {
+        Some(topic) if !topic.is_empty() => topic,
+        _ => ALL_TOPICS.to_string(),
+    }
+}
+
+// endregion:    -- Support
@@ -0,0 +1,259 @@
+// NOTE: The graded readers: the laoshi writes a short story on a topic,
+// checked offline against the words the learner knows (the vocabulary deck,
+// and the words up to the learner HSK level). When too many words are
+// unknown, the laoshi is asked to rewrite it with simpler words, up to
+// `MAX_READER_REWRITES` times. Each reader has its own thread, so it does
+// not pollute the conversation (deleted by `end_reader`).
+//
+// The readers are saved as markdown, with a glossary of the new words, in
+// laoshi/.laoshi/readers/ (e.g., 2026-10-18-at-the-market.md).
+use crate::ais::assistant;
+use crate::chinese::{
+    format_pinyin, segment, Cedict, CedictEntry, Token, ToneStyle,
+};
+use crate::laoshi::{data_dir, Conversation, Laoshi};
+use crate::Result;
+use chrono::Local;
+use simple_fs::ensure_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const READERS_DIR: &str = "readers";
+
+/// The rewrites asked for when too many words are unknown.
+pub const MAX_READER_REWRITES: usize = 2;
+
+/// The share of unknown words that asks for a rewrite.
+// NOTE: Not 0, the new words are the point of a reader (and the names)
+pub const MAX_UNKNOWN_SHARE: f32 = 0.1;
+
+/// The known words sent as examples in the reader request.
+const MAX_EXAMPLE_WORDS: usize = 150;
+
+// region:       -- Types
+
+#[derive(Debug)]
+pub struct Reader {
+    pub topic: String,
+    /// The markdown story (a `# ` title line, then the text)
+    pub story: String,
+    pub rewrites: usize,
+    conversation: Conversation,
+}
+
+/// The offline check of a reader.
+#[derive(Debug)]
+pub struct ReaderCheck {
+    /// The hanzi words (repeated words included)
+    pub num_words: usize,
+    /// The distinct unknown words, in the text order
+    pub unknown: Vec<String>,
+    /// The share (0.0 to 1.0) of the words that are unknown
+    pub unknown_share: f32,
+}
+
+// endregion:    -- Types
+
+impl Laoshi {
+    /// A new reader on the `topic`, for the `level` (e.g., "HSK 3") and the
+    /// `known_words` of the learner (only some are sent).
+    pub async fn new_reader(
+        &self,
+        topic: &str,
+        level: Option<&str>,
+        known_words: &[&str],
+    ) -> Result<Reader> {
+        let mut conversation: Conversation =
+            assistant::create_thread(&self.oac).await?.into();
+
+        let level_text = match level {
+            Some(level) => format!("for a learner at {level}"),
+            None => "for a beginner learner".to_string(),
+        };
+        let known_text = match known_words.is_empty() {
+            true => String::new(),
+            false => format!(
+                "\nThe learner knows these words, use them as much as you can: {}.",
+                known_words
+                    .iter()
+                    .take(MAX_EXAMPLE_WORDS)
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let msg = format!(
+            "Write a short graded reader in Chinese about \"{topic}\", {level_text} \
+             (about 150 to 300 characters). Use simple sentences and mostly words \
+             the learner knows, with only a few new ones.{known_text}\n\
+             Respond ONLY with the story, no translation or explanation: \
+             a first line with the title, starting with \"# \", then the story."
+        );
+        let story = match self.chat(&mut conversation, &msg).await {
+            Ok(story) => story,
+            // NOTE: No reader, no thread left behind
+            Err(err) => {
+                self.delete_conversation(&conversation).await;
+                return Err(err);
+            }
+        };
+
+        Ok(Reader {
+            topic: topic.to_string(),
+            story: story.trim().to_string(),
+            rewrites: 0,
+            conversation,
+        })
+    }
+
+    /// Asks to rewrite the `reader` without most of its `unknown` words.
+    pub async fn rewrite_reader(
+        &self,
+        reader: &mut Reader,
+        unknown: &[String],
+    ) -> Result<()> {
+        let msg = format!(
+            "Too many words of the story are new to the learner: {}.\n\
+             Rewrite the story with simpler words the learner knows instead \
+             (keep a few new ones at most). Respond ONLY with the story, \
+             the same way (a \"# \" title line, then the story).",
+            unknown.join(", ")
+        );
+        let story = self.chat(&mut reader.conversation, &msg).await?;
+        reader.story = story.trim().to_string();
+        reader.rewrites += 1;
+        Ok(())
+    }
+
+    /// Ends the `reader` rewrites (deletes its thread), the story is kept.
+    pub async fn end_reader(&self, reader: &Reader) {
+        self.delete_conversation(&reader.conversation).await;
+    }
+}
+
+impl Reader {
+    /// Checks the words of the story (offline), `is_known` tells if the
+    /// learner knows a word.
+    pub fn check(
+        &self,
+        cedict: &Cedict,
+        is_known: impl Fn(&str) -> bool,
+    ) -> ReaderCheck {
+        let words: Vec<&str> = segment(&self.story, cedict)
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Word(word) => Some(word),
+                Token::Text(_) => None,
+            })
+            .collect();
+
+        let mut unknown: Vec<String> = Vec::new();
+        let mut num_unknown = 0;
+        for word in &words {
+            if is_known(word) {
+                continue;
+            }
+            num_unknown += 1;
+            if !unknown.iter().any(|w| w == word) {
+                unknown.push(word.to_string());
+            }
+        }
+
+        ReaderCheck {
+            num_words: words.len(),
+            unknown,
+            unknown_share: match words.len() {
+                0 => 0.0,
+                n => num_unknown as f32 / n as f32,
+            },
+        }
+    }
+
+    /// The title of the story (the topic if none).
+    pub fn title(&self) -> &str {
+        self.story
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .unwrap_or(&self.topic)
+    }
+
+    /// Saves the reader as markdown in the laoshi `dir`, with the glossary
+    /// of its `new_words` (their CC-CEDICT entry, if any).
+    pub fn save(
+        &self,
+        dir: impl AsRef<Path>,
+        new_words: &[String],
+        cedict: &Cedict,
+    ) -> Result<PathBuf> {
+        let readers_dir = data_dir(dir.as_ref())?.join(READERS_DIR);
+        ensure_dir(&readers_dir)?;
+        let today = Local::now().date_naive();
+        // NOTE: Another reader on the topic the same day is kept (e.g., "-2")
+        let name = format!("{today}-{}", slug(&self.topic));
+        let file = (1..)
+            .map(|n| match n {
+                1 => readers_dir.join(format!("{name}.md")),
+                n => readers_dir.join(format!("{name}-{n}.md")),
+            })
+            .find(|file| !file.exists())
+            .unwrap_or_else(|| readers_dir.join(format!("{name}.md")));
+
+        // -- The story (with a title line)
+        let mut content = match self.story.starts_with("# ") {
+            true => self.story.clone(),
+            false => format!("# {}\n\n{}", self.topic, self.story),
+        };
+        content.push_str(&format!("\n\n_Topic: {}, {today}_\n", self.topic));
+
+        // -- The glossary
+        let entries: Vec<(&str, Option<&CedictEntry>)> = new_words
+            .iter()
+            .map(|word| (word.as_str(), cedict.best_entry(word)))
+            .collect();
+        if !entries.is_empty() {
+            content.push_str(
+                "\n## Glossary\n\n| Word | Pinyin | Meaning |\n|---|---|---|\n",
+            );
+            for (word, entry) in entries {
+                let (pinyin, meaning) = match entry {
+                    Some(entry) => (
+                        format_pinyin(&entry.pinyin, ToneStyle::Marks),
+                        entry.definitions.join("; ").replace('|', "/"),
+                    ),
+                    None => (String::new(), String::new()),
+                };
+                content.push_str(&format!("| {word} | {pinyin} | {meaning} |\n"));
+            }
+        }
+
+        fs::write(&file, content)?;
+        Ok(file)
+    }
+}
+
+impl ReaderCheck {
+    /// `true` if too many words are unknown (see `MAX_UNKNOWN_SHARE`).
+    pub fn too_hard(&self) -> bool {
+        self.unknown_share > MAX_UNKNOWN_SHARE
+    }
+}
+
+// region:       -- Support
+
+/// e.g., "At the Market!" -> "at-the-market", "在市场" -> "在市场"
+fn slug(topic: &str) -> String {
+    let slug = topic
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    match slug.is_empty() {
+        true => "reader".to_string(),
+        false => slug,
+    }
+}
+
+// endregion:    -- Support
@@ -0,0 +1,164 @@
+// NOTE: The `/status` and `laoshi status` info: what a session is using on
+// the OpenAI side (assistant, instructions, bundle files, thread) and the
+// tokens used so far, without opening the OpenAI dashboard.
+use crate::ais::assistant::{self, RunUsage};
+use crate::ais::{AssistantId, FileId};
+use crate::laoshi::bundles::bundle_file_name;
+use crate::laoshi::config::Config;
+use crate::laoshi::{
+    data_dir, instructions, Conversation, Laoshi, CONV_JSON, LAOSHI_TOML,
+};
+use crate::utils::hash::sha256_hex;
+use crate::Result;
+use async_openai::types::AssistantObject;
+use async_openai::{config::OpenAIConfig, Client};
+use simple_fs::load_json;
+use std::collections::HashMap;
+use std::path::Path;
+
+// region:       -- Types
+
+#[derive(Debug)]
+pub struct Status {
+    pub name: String,
+    /// `None` when the assistant is not created yet
+    pub assistant_id: Option<String>,
+    /// The remote model (the config model if no assistant yet)
+    pub model: String,
+    /// The SHA-256 of the remote instructions
+    pub instructions_hash: Option<String>,
+    /// `true` if the local instructions differ from the remote ones
+    /// (`false` without local instructions, they are unmanaged)
+    pub instructions_changed: bool,
+    pub bundles: Vec<BundleStatus>,
+    pub thread_id: Option<String>,
+    /// `None` outside of a session (e.g., `laoshi status`)
+    pub usage: Option<RunUsage>,
+}
+
+#[derive(Debug)]
+pub struct BundleStatus {
+    pub bundle_name: String,
+    /// The number of local source files
+    pub num_files: usize,
+    /// The total size of the local source files, in bytes
+    pub size: u64,
+    /// The remote file (`None` if not uploaded)
+    pub file_id: Option<String>,
+}
+
+// endregion:    -- Types
+
+impl Status {
+    /// The status of the laoshi `dir` (only reads the remote state, and the
+    /// saved conversation).
+    pub async fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let config = Config::load(dir, LAOSHI_TOML)?;
+        let oac = config.new_openai_client()?;
+        let assistant_obj = assistant::first_by_name(&oac, &config.name).await?;
+
+        // NOTE: The thread of the saved conversation (not checked remotely)
+        let conversation: Option<Conversation> =
+            load_json(data_dir(dir)?.join(CONV_JSON)).ok();
+        let thread_id = conversation.map(|conv| conv.to_string());
+
+        build(dir, &config, &oac, assistant_obj, thread_id, None).await
+    }
+}
+
+impl Laoshi {
+    /// The status of this laoshi session, with the `conv` thread.
+    pub async fn status(&self, conv: &Conversation) -> Result<Status> {
+        let assistant_obj =
+            self.oac.assistants().retrieve(&self.assistant_id).await?;
+
+        build(
+            &self.dir,
+            &self.config,
+            &self.oac,
+            Some(assistant_obj),
+            Some(conv.to_string()),
+            Some(self.session_usage()),
+        )
+        .await
+    }
+}
+
+// region:       -- Support
+
+async fn build(
+    dir: &Path,
+    config: &Config,
+    oac: &Client<OpenAIConfig>,
+    assistant_obj: Option<AssistantObject>,
+    thread_id: Option<String>,
+    usage: Option<RunUsage>,
+) -> Result<Status> {
+    let local_ix = instructions::compose(dir, config)?;
+
+    let (assistant_id, model, remote_ix, remote_files) = match assistant_obj {
+        Some(assistant_obj) => {
+            let assistant_id = AssistantId::from(assistant_obj.id);
+            let remote_files =
+                assistant::get_files_hashmap(oac, &assistant_id).await?;
+            (
+                Some(assistant_id),
+                assistant_obj.model,
+                assistant_obj.instructions,
+                remote_files,
+            )
+        }
+        None => (None, config.model.clone(), None, HashMap::new()),
+    };
+
+    let instructions_hash = remote_ix.as_ref().map(sha256_hex);
+    // NOTE: No local instructions means unmanaged (same as the `Plan`)
+    let instructions_changed = match local_ix {
+        Some(local_ix) => remote_ix.as_deref().unwrap_or_default() != local_ix,
+        None => false,
+    };
+
+    let bundles = bundles_status(dir, config, assistant_id.as_ref(), &remote_files)?;
+
+    Ok(Status {
+        name: config.name.clone(),
+        assistant_id: assistant_id.map(|id| id.to_string()),
+        model,
+        instructions_hash,
+        instructions_changed,
+        bundles,
+        thread_id,
+        usage,
+    })
+}
+
+fn bundles_status(
+    dir: &Path,
+    config: &Config,
+    assistant_id: Option<&AssistantId>,
+    remote_files: &HashMap<String, FileId>,
+) -> Result<Vec<BundleStatus>> {
+    let mut bundles = Vec::new();
+    for bundle in config.file_bundles.iter() {
+        let files = bundle.src_files(dir)?;
+        let size = files
+            .iter()
+            .map(|file| file.file_size().unwrap_or_default().max(0) as u64)
+            .sum();
+        let file_id = assistant_id
+            .map(|assistant_id| bundle_file_name(&config.name, bundle, assistant_id))
+            .and_then(|file_name| remote_files.get(&file_name))
+            .map(|file_id| file_id.to_string());
+
+        bundles.push(BundleStatus {
+            bundle_name: bundle.bundle_name.clone(),
+            num_files: files.len(),
+            size,
+            file_id,
+        });
+    }
+    Ok(bundles)
+}
+
+// endregion:    -- Support
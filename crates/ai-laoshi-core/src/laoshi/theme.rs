@@ -0,0 +1,61 @@
+// NOTE: The [theme] config section (usually in the global config), applied
+// by the UIs. Loaded on its own (see `Laoshi::theme_config_from_dir`), so an
+// invalid laoshi config does not prevent the UI from reporting it.
+//
+// [theme]
+// ascii = true             # ASCII-only icons and rules (e.g., screen readers)
+// prompt = "Ask away!"
+// [theme.colors]           # 256-color indexes or names ("green", "red", ...)
+// accent = 45
+// success = "green"
+// [theme.icons]
+// res = ">"
+use crate::laoshi::layers::ConfigLayers;
+use crate::Result;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// ASCII-only icons, bullets and rules
+    pub ascii: bool,
+    /// The chat prompt text
+    pub prompt: Option<String>,
+    pub colors: ThemeColors,
+    pub icons: ThemeIcons,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    /// Prompts, headings, links, the response icon
+    pub accent: Option<ThemeColor>,
+    pub success: Option<ThemeColor>,
+    pub warning: Option<ThemeColor>,
+    pub error: Option<ThemeColor>,
+}
+
+/// A 256-color index (e.g., `45`) or a color name (e.g., `"green"`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ThemeColor {
+    Index(u8),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeIcons {
+    pub res: Option<String>,
+    pub check: Option<String>,
+    pub uploading: Option<String>,
+    pub uploaded: Option<String>,
+    pub deleted: Option<String>,
+    pub err: Option<String>,
+}
+
+/// Loads the merged [theme] of the laoshi `dir` config (default if none).
+pub(super) fn load(dir: &Path, project_toml: &str) -> Result<ThemeConfig> {
+    ConfigLayers::load(dir, project_toml)?.section("theme", project_toml)
+}
@@ -0,0 +1,75 @@
+// NOTE: The [tutor] config section, for the Mandarin tutor display of the UIs.
+//
+// [tutor]
+// pinyin = "ruby"              # off (default), ruby (lines above) or inline
+// tones = "marks"              # marks (nǐhǎo) or numbers (ni3hao3)
+// cedict_file = "cedict_ts.u8" # CC-CEDICT, relative to the laoshi dir
+// auto_save = false            # save the new words (above hsk_level) to the deck
+// hsk_level = 3                # the learner level, if none in the profile
+// hsk_version = "2.0"          # the HSK word lists, "2.0" (1-6) or "3.0" (1-9)
+// hsk_dir = "hsk"              # the word lists, relative to the laoshi dir
+// hsk_rewrite = false          # ask to rewrite the responses above hsk_level
+// correct = false              # correct the Chinese of the learner messages
+// opencc_dir = "opencc"        # OpenCC script tables, relative to the laoshi dir
+use crate::chinese::{HskVersion, ToneStyle, CEDICT_FILE, HSK_DIR, OPENCC_DIR};
+use crate::laoshi::layers::ConfigLayers;
+use crate::Result;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TutorConfig {
+    pub pinyin: PinyinDisplay,
+    pub tones: ToneStyle,
+    pub cedict_file: Option<String>,
+    /// Saves the new words of the responses to the vocabulary deck
+    pub auto_save: bool,
+    /// The learner HSK level (the HSK level of the profile wins)
+    pub hsk_level: Option<u8>,
+    pub hsk_version: HskVersion,
+    pub hsk_dir: Option<String>,
+    /// Asks the laoshi to rewrite the responses above the `hsk_level`
+    pub hsk_rewrite: bool,
+    /// Corrects the Chinese of the learner messages (grammar mode)
+    pub correct: bool,
+    /// The optional OpenCC tables of the script conversion
+    pub opencc_dir: Option<String>,
+}
+
+/// How the UIs show the pinyin of the Chinese words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PinyinDisplay {
+    #[default]
+    Off,
+    /// On a line above the hanzi
+    Ruby,
+    /// In brackets after the hanzi
+    Inline,
+}
+
+impl TutorConfig {
+    /// The CC-CEDICT file of the laoshi `dir`.
+    pub fn cedict_path(&self, dir: impl AsRef<Path>) -> PathBuf {
+        dir.as_ref()
+            .join(self.cedict_file.as_deref().unwrap_or(CEDICT_FILE))
+    }
+
+    /// The HSK word lists dir of the laoshi `dir`.
+    pub fn hsk_path(&self, dir: impl AsRef<Path>) -> PathBuf {
+        dir.as_ref()
+            .join(self.hsk_dir.as_deref().unwrap_or(HSK_DIR))
+    }
+
+    /// The OpenCC tables dir of the laoshi `dir`.
+    pub fn opencc_path(&self, dir: impl AsRef<Path>) -> PathBuf {
+        dir.as_ref()
+            .join(self.opencc_dir.as_deref().unwrap_or(OPENCC_DIR))
+    }
+}
+
+/// Loads the merged [tutor] of the laoshi `dir` config (default if none).
+pub(super) fn load(dir: &Path, project_toml: &str) -> Result<TutorConfig> {
+    ConfigLayers::load(dir, project_toml)?.section("tutor", project_toml)
+}
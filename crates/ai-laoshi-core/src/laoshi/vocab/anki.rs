@@ -0,0 +1,312 @@
+// NOTE: The Anki package (.apkg) export of the deck: a zip with a (legacy,
+// schema 11) `collection.anki2` SQLite database and an empty `media` map,
+// which all the Anki versions import.
+//
+// The ids are stable: the note type and deck ids are constants, and the
+// note guid is the card id, so Anki updates the notes of a re-exported deck
+// (keeping its own scheduling) instead of adding duplicates.
+use crate::laoshi::vocab::{Card, Deck};
+use crate::utils::hash::sha256_hex;
+use crate::Result;
+use rusqlite::{params, Connection};
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const ANKI_DECK_NAME: &str = "Laoshi Vocabulary";
+const ANKI_DECK_ID: i64 = 1_709_000_000_042;
+const ANKI_MODEL_NAME: &str = "Laoshi Vocabulary";
+const ANKI_MODEL_ID: i64 = 1_709_000_000_043;
+const ANKI_FIELDS: &[&str] =
+    &["Hanzi", "Traditional", "Pinyin", "Definition", "Example"];
+
+const ANKI_QFMT: &str = r#"<div class="hanzi">{{Hanzi}}</div>"#;
+const ANKI_AFMT: &str = r#"{{FrontSide}}<hr id="answer">
+<div class="pinyin">{{Pinyin}}</div>
+<div class="definition">{{Definition}}</div>
+{{#Example}}<div class="example">{{Example}}</div>{{/Example}}"#;
+const ANKI_CSS: &str =
+    ".card { font-family: sans-serif; font-size: 20px; text-align: center; }
+.hanzi { font-size: 48px; }
+.pinyin { color: #1e88e5; }
+.example { color: #777; margin-top: 12px; }";
+
+impl Deck {
+    /// Exports the deck as an Anki package `file` (e.g., `deck.apkg`).
+    pub fn export_anki(&self, file: impl AsRef<Path>) -> Result<()> {
+        let file = file.as_ref();
+        // NOTE: SQLite needs a file, zipped then removed
+        let db_file = std::env::temp_dir()
+            .join(format!("laoshi-vocab-{}.anki2", std::process::id()));
+        let _ = fs::remove_file(&db_file);
+
+        let res = write_collection(&db_file, &self.cards)
+            .and_then(|_| write_package(file, &fs::read(&db_file)?));
+        let _ = fs::remove_file(&db_file);
+        res
+    }
+}
+
+// region:       -- Collection
+
+fn write_collection(db_file: &Path, cards: &[Card]) -> Result<()> {
+    let now = chrono::Utc::now();
+    let (now_s, now_ms) = (now.timestamp(), now.timestamp_millis());
+
+    let conn = Connection::open(db_file)?;
+    conn.execute_batch(SCHEMA)?;
+    conn.execute(
+        "INSERT INTO col VALUES (1, ?1, ?2, ?2, 11, 0, 0, 0, ?3, ?4, ?5, ?6, '{}')",
+        params![
+            now_s - now_s % 86400,
+            now_ms,
+            col_conf().to_string(),
+            col_models(now_s).to_string(),
+            col_decks(now_s).to_string(),
+            col_dconf().to_string(),
+        ],
+    )?;
+
+    for (pos, card) in cards.iter().enumerate() {
+        let (note_id, card_id) = anki_ids(&card.id);
+        let fields = [
+            card.simplified.as_str(),
+            &card.traditional,
+            &card.pinyin,
+            &card.definition,
+            card.example.as_deref().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| html_escape(f)).collect();
+
+        conn.execute(
+            "INSERT INTO notes VALUES (?1, ?2, ?3, ?4, -1, '', ?5, ?6, ?7, 0, '')",
+            params![
+                note_id,
+                card.id,
+                ANKI_MODEL_ID,
+                now_s,
+                fields.join("\x1f"),
+                card.simplified,
+                checksum(&card.simplified),
+            ],
+        )?;
+        // NOTE: New cards (Anki schedules them), in the deck order
+        conn.execute(
+            "INSERT INTO cards VALUES
+             (?1, ?2, ?3, 0, ?4, -1, 0, 0, ?5, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+            params![card_id, note_id, ANKI_DECK_ID, now_s, pos as i64 + 1],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn col_conf() -> serde_json::Value {
+    json!({
+        "activeDecks": [1],
+        "curDeck": 1,
+        "curModel": ANKI_MODEL_ID.to_string(),
+        "newSpread": 0,
+        "collapseTime": 1200,
+        "timeLim": 0,
+        "estTimes": true,
+        "dueCounts": true,
+        "nextPos": 1,
+        "sortType": "noteFld",
+        "sortBackwards": false,
+        "addToCur": true,
+    })
+}
+
+fn col_models(now_s: i64) -> serde_json::Value {
+    let flds: Vec<serde_json::Value> = ANKI_FIELDS
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            json!({
+                "name": name,
+                "ord": ord,
+                "sticky": false,
+                "rtl": false,
+                "font": "Arial",
+                "size": 20,
+                "media": [],
+            })
+        })
+        .collect();
+    json!({
+        ANKI_MODEL_ID.to_string(): {
+            "id": ANKI_MODEL_ID,
+            "name": ANKI_MODEL_NAME,
+            "type": 0,
+            "mod": now_s,
+            "usn": -1,
+            "sortf": 0,
+            "did": ANKI_DECK_ID,
+            "tmpls": [{
+                "name": "Recognition",
+                "ord": 0,
+                "qfmt": ANKI_QFMT,
+                "afmt": ANKI_AFMT,
+                "did": null,
+                "bqfmt": "",
+                "bafmt": "",
+            }],
+            "flds": flds,
+            "css": ANKI_CSS,
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "tags": [],
+            "vers": [],
+            // NOTE: The card needs the Hanzi field
+            "req": [[0, "any", [0]]],
+        }
+    })
+}
+
+fn col_decks(now_s: i64) -> serde_json::Value {
+    let deck = |id: i64, name: &str| {
+        json!({
+            "id": id,
+            "name": name,
+            "desc": "",
+            "mod": now_s,
+            "usn": -1,
+            "collapsed": false,
+            "dyn": 0,
+            "conf": 1,
+            "extendNew": 10,
+            "extendRev": 50,
+            "newToday": [0, 0],
+            "revToday": [0, 0],
+            "lrnToday": [0, 0],
+            "timeToday": [0, 0],
+        })
+    };
+    json!({
+        "1": deck(1, "Default"),
+        ANKI_DECK_ID.to_string(): deck(ANKI_DECK_ID, ANKI_DECK_NAME),
+    })
+}
+
+fn col_dconf() -> serde_json::Value {
+    json!({
+        "1": {
+            "id": 1,
+            "name": "Default",
+            "mod": 0,
+            "usn": 0,
+            "maxTaken": 60,
+            "autoplay": true,
+            "timer": 0,
+            "replayq": true,
+            "new": {
+                "bury": true,
+                "delays": [1, 10],
+                "initialFactor": 2500,
+                "ints": [1, 4, 7],
+                "order": 1,
+                "perDay": 20,
+                "separate": true,
+            },
+            "rev": {
+                "bury": true,
+                "ease4": 1.3,
+                "fuzz": 0.05,
+                "ivlFct": 1,
+                "maxIvl": 36500,
+                "minSpace": 1,
+                "perDay": 100,
+            },
+            "lapse": {
+                "delays": [10],
+                "leechAction": 0,
+                "leechFails": 8,
+                "minInt": 1,
+                "mult": 0,
+            },
+        }
+    })
+}
+
+const SCHEMA: &str = "
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null,
+    scm integer not null, ver integer not null, dty integer not null,
+    usn integer not null, ls integer not null, conf text not null,
+    models text not null, decks text not null, dconf text not null,
+    tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null,
+    mod integer not null, usn integer not null, tags text not null,
+    flds text not null, sfld integer not null, csum integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null,
+    ord integer not null, mod integer not null, usn integer not null,
+    type integer not null, queue integer not null, due integer not null,
+    ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null,
+    odid integer not null, flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null,
+    ease integer not null, ivl integer not null, lastIvl integer not null,
+    factor integer not null, time integer not null, type integer not null
+);
+CREATE TABLE graves (
+    usn integer not null, oid integer not null, type integer not null
+);
+CREATE INDEX ix_notes_usn on notes (usn);
+CREATE INDEX ix_cards_usn on cards (usn);
+CREATE INDEX ix_revlog_usn on revlog (usn);
+CREATE INDEX ix_cards_nid on cards (nid);
+CREATE INDEX ix_cards_sched on cards (did, queue, due);
+CREATE INDEX ix_revlog_cid on revlog (cid);
+CREATE INDEX ix_notes_csum on notes (csum);
+";
+
+// endregion:    -- Collection
+
+// region:       -- Support
+
+fn write_package(file: &Path, collection: &[u8]) -> Result<()> {
+    let mut zip = ZipWriter::new(File::create(file)?);
+    let options =
+        SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("collection.anki2", options)?;
+    zip.write_all(collection)?;
+    zip.start_file("media", options)?;
+    zip.write_all(b"{}")?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// The (note, card) ids of a deck card id (stable, under 2^48).
+fn anki_ids(card_id: &str) -> (i64, i64) {
+    let hash = sha256_hex(card_id.as_bytes());
+    let id = |hex: &str| i64::from_str_radix(hex, 16).unwrap_or_default() + 1;
+    (id(&hash[..12]), id(&hash[12..24]))
+}
+
+/// The Anki duplicate checksum of the first field.
+fn checksum(sort_field: &str) -> i64 {
+    let hash = Sha1::digest(sort_field.as_bytes());
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]) as i64
+}
+
+/// The Anki fields are HTML.
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// endregion:    -- Support
@@ -0,0 +1,391 @@
+// NOTE: The vocabulary deck: the Chinese words saved from the conversations
+// (`/save`, or automatically from the responses), with their pinyin and
+// definition from the local CC-CEDICT, reviewed with the SM-2 spaced
+// repetition algorithm (https://super-memory.com/english/ol/sm2.htm).
+// Kept in laoshi/.laoshi/vocab.json (not uploaded), exported to Anki (.apkg,
+// with the `anki` feature) or TSV, and imported from TSV.
+
+// region:       -- Modules
+
+#[cfg(feature = "anki")]
+mod anki;
+mod tsv;
+
+pub use tsv::ImportSummary;
+
+// endregion:    -- Modules
+
+use crate::chinese::{
+    format_pinyin, is_hanzi, segment, Cedict, HskLists, Token, ToneStyle,
+};
+use crate::laoshi::data_dir;
+use crate::utils::hash::sha256_hex;
+use crate::Result;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use simple_fs::{load_json, save_json};
+use std::path::{Path, PathBuf};
+
+const VOCAB_JSON: &str = "vocab.json";
+
+/// The SM-2 starting ease factor.
+const INITIAL_EASE: f32 = 2.5;
+/// The SM-2 lowest ease factor.
+const MIN_EASE: f32 = 1.3;
+/// The longest example sentence kept from a response.
+const MAX_EXAMPLE_CHARS: usize = 80;
+
+// region:       -- Types
+
+#[derive(Debug, Default)]
+pub struct Deck {
+    file: PathBuf,
+    cards: Vec<Card>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Card {
+    /// Stable across sessions (derived from the simplified hanzi)
+    pub id: String,
+    pub simplified: String,
+    pub traditional: String,
+    /// With tone marks (e.g., "xuéxí")
+    pub pinyin: String,
+    pub definition: String,
+    /// A sentence of the conversation using the word
+    #[serde(default)]
+    pub example: Option<String>,
+    pub added: NaiveDate,
+    // -- SM-2 state
+    pub ease: f32,
+    /// The days until the next review
+    pub interval: u32,
+    /// The successful reviews in a row
+    pub repetitions: u32,
+    pub due: NaiveDate,
+    #[serde(default)]
+    pub history: Vec<ReviewLog>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLog {
+    pub date: NaiveDate,
+    pub grade: u8,
+    /// The interval set by this review
+    pub interval: u32,
+}
+
+// endregion:    -- Types
+
+impl Card {
+    /// The card of the `word` (simplified or traditional) from the `cedict`
+    /// (`None` if the word is not in the dictionary).
+    pub fn from_cedict(
+        word: &str,
+        cedict: &Cedict,
+        today: NaiveDate,
+    ) -> Option<Self> {
+        let entry = cedict.best_entry(word)?;
+        Some(Self::new(
+            &entry.simplified,
+            &entry.traditional,
+            &format_pinyin(&entry.pinyin, ToneStyle::Marks),
+            &entry.definitions.join("; "),
+            today,
+        ))
+    }
+
+    /// A new card (due `today`).
+    pub fn new(
+        simplified: &str,
+        traditional: &str,
+        pinyin: &str,
+        definition: &str,
+        today: NaiveDate,
+    ) -> Self {
+        Self {
+            id: card_id(simplified),
+            simplified: simplified.to_string(),
+            traditional: traditional.to_string(),
+            pinyin: pinyin.to_string(),
+            definition: definition.to_string(),
+            example: None,
+            added: today,
+            ease: INITIAL_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: today,
+            history: Vec::new(),
+        }
+    }
+
+    /// Sets the example to the first sentence of the `text` using the word
+    /// (if the card has no example yet).
+    pub fn set_example_from(&mut self, text: &str) {
+        if self.example.is_some() {
+            return;
+        }
+        self.example = example_sentence(text, &self.simplified)
+            .or_else(|| example_sentence(text, &self.traditional));
+    }
+
+    pub fn is_due(&self, today: NaiveDate) -> bool {
+        self.due <= today
+    }
+
+    /// Applies a review `grade` (0 to 5, 3+ is a correct answer) with SM-2.
+    pub fn review(&mut self, grade: u8, today: NaiveDate) {
+        let grade = grade.min(5);
+        if grade >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f32 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            // NOTE: A failed card starts over (the ease still goes down)
+            self.interval = 1;
+            self.repetitions = 0;
+        }
+
+        let miss = (5 - grade) as f32;
+        let ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+        // NOTE: Rounded, to keep the deck file readable (e.g., 1.92)
+        self.ease = (ease * 100.0).round() / 100.0;
+        self.due = today
+            .checked_add_days(Days::new(self.interval as u64))
+            .unwrap_or(today);
+        self.history.push(ReviewLog {
+            date: today,
+            grade,
+            interval: self.interval,
+        });
+    }
+}
+
+impl Deck {
+    /// Loads the deck of the laoshi `dir` (empty if none yet).
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let file = data_dir(dir.as_ref())?.join(VOCAB_JSON);
+        // NOTE: An invalid deck file is an error (not reset on the next save)
+        let cards = match file.is_file() {
+            true => load_json(&file)?,
+            false => Vec::new(),
+        };
+        Ok(Self { file, cards })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save_json(&self.file, &self.cards)?;
+        Ok(())
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// `true` if the `word` (simplified or traditional) is in the deck.
+    pub fn contains(&self, word: &str) -> bool {
+        self.cards
+            .iter()
+            .any(|card| card.simplified == word || card.traditional == word)
+    }
+
+    /// Adds the `card` if not in the deck yet (returns `false` otherwise).
+    pub fn add(&mut self, card: Card) -> bool {
+        if self.cards.iter().any(|c| c.id == card.id) {
+            return false;
+        }
+        self.cards.push(card);
+        true
+    }
+
+    /// Adds the new words of the `text` (e.g., a response) to the deck, and
+    /// returns them (in the text order). With the `known` HSK lists and
+    /// learner level, the words at or below that level are skipped.
+    // NOTE: Only the dictionary words of 2+ hanzi, the single characters are
+    // mostly particles and grammar words (了, 的, 吗) the learner knows.
+    pub fn capture(
+        &mut self,
+        text: &str,
+        cedict: &Cedict,
+        known: Option<(&HskLists, u8)>,
+        today: NaiveDate,
+    ) -> Vec<String> {
+        let mut added = Vec::new();
+        for token in segment(text, cedict) {
+            let Token::Word(word) = token else {
+                continue;
+            };
+            if word.chars().filter(|c| is_hanzi(*c)).count() < 2
+                || self.contains(word)
+            {
+                continue;
+            }
+            // NOTE: The unlisted words (e.g., names) are not known
+            if let Some((lists, level)) = known {
+                if lists.level(word, cedict).is_some_and(|l| l <= level) {
+                    continue;
+                }
+            }
+            if let Some(mut card) = Card::from_cedict(word, cedict, today) {
+                card.set_example_from(text);
+                let simplified = card.simplified.clone();
+                if self.add(card) {
+                    added.push(simplified);
+                }
+            }
+        }
+        added
+    }
+
+    /// The ids of the cards due `today`, the most overdue first.
+    pub fn due_ids(&self, today: NaiveDate) -> Vec<String> {
+        let mut due: Vec<&Card> = self
+            .cards
+            .iter()
+            .filter(|card| card.is_due(today))
+            .collect();
+        due.sort_by_key(|card| card.due);
+        due.into_iter().map(|card| card.id.clone()).collect()
+    }
+
+    pub fn card(&self, id: &str) -> Option<&Card> {
+        self.cards.iter().find(|card| card.id == id)
+    }
+
+    pub fn card_mut(&mut self, id: &str) -> Option<&mut Card> {
+        self.cards.iter_mut().find(|card| card.id == id)
+    }
+}
+
+// region:       -- Support
+
+/// e.g., "vocab-3f2a9c0b1d4e5f67"
+fn card_id(simplified: &str) -> String {
+    format!("vocab-{}", &sha256_hex(simplified.as_bytes())[..16])
+}
+
+/// The first sentence of the `text` with the `word` (markdown removed).
+fn example_sentence(text: &str, word: &str) -> Option<String> {
+    text.split_inclusive(['。', '！', '？', '!', '?', '\n'])
+        .map(|sentence| {
+            let sentence = sentence.replace(['*', '`'], "");
+            sentence
+                .trim()
+                .trim_start_matches(['#', '>', '-', ' '])
+                .to_string()
+        })
+        .find(|sentence| {
+            sentence.contains(word) && sentence.chars().count() <= MAX_EXAMPLE_CHARS
+        })
+}
+
+// endregion:    -- Support
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+    use crate::chinese::HskVersion;
+
+    #[test]
+    fn test_card_review_sm2_intervals() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_today = NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("date")?;
+        let mut card = Card::new("学习", "學習", "xuéxí", "to learn", fx_today);
+
+        // -- Exec & Check
+        // grade 4 keeps the ease, the intervals are 1, 6, then interval * ease
+        card.review(4, fx_today);
+        assert_eq!((card.interval, card.repetitions, card.ease), (1, 1, 2.5));
+        assert_eq!(card.due, NaiveDate::from_ymd_opt(2024, 1, 2).ok_or("date")?);
+        card.review(4, fx_today);
+        assert_eq!((card.interval, card.repetitions), (6, 2));
+        card.review(4, fx_today);
+        assert_eq!((card.interval, card.repetitions), (15, 3));
+        assert_eq!(
+            card.due,
+            NaiveDate::from_ymd_opt(2024, 1, 16).ok_or("date")?
+        );
+        // grade 5 raises the ease
+        card.review(5, fx_today);
+        assert_eq!(card.ease, 2.6);
+        assert_eq!(card.history.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_review_sm2_failed() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_today = NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("date")?;
+        let mut card = Card::new("学习", "學習", "xuéxí", "to learn", fx_today);
+        card.review(5, fx_today);
+        card.review(5, fx_today);
+
+        // -- Exec
+        card.review(0, fx_today);
+
+        // -- Check
+        // starts over, the ease goes down (and not below the min)
+        assert_eq!((card.interval, card.repetitions), (1, 0));
+        assert_eq!(card.ease, 1.9);
+        card.review(0, fx_today);
+        card.review(0, fx_today);
+        assert_eq!(card.ease, MIN_EASE);
+        assert_eq!(card.history.last().map(|log| log.grade), Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deck_capture_skips_known_level() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_today = NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("date")?;
+        let fx_cedict = Cedict::parse(
+            "\
+學習 学习 [xue2 xi2] /to learn/
+經濟 经济 [jing1 ji4] /economy/
+朋友 朋友 [peng2 you5] /friend/
+王明 王明 [Wang2 Ming2] /Wang Ming/
+",
+        );
+        let fx_text = "我和朋友王明学习经济。";
+        let fx_lists = HskLists::load("no-hsk-dir", HskVersion::V2)?;
+
+        // -- Exec
+        let all = Deck::default().capture(fx_text, &fx_cedict, None, fx_today);
+        let above = Deck::default().capture(
+            fx_text,
+            &fx_cedict,
+            Some((&fx_lists, 3)),
+            fx_today,
+        );
+
+        // -- Check
+        assert_eq!(all, ["朋友", "王明", "学习", "经济"]);
+        // 朋友, 学习 (HSK 1) are known, 经济 (HSK 4) and 王明 (unlisted) not
+        assert_eq!(above, ["王明", "经济"]);
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
@@ -0,0 +1,219 @@
+// NOTE: The TSV export/import of the deck, with the Anki file headers
+// (https://docs.ankiweb.net/importing/text-files.html), so the file can be
+// imported in Anki as well. The `id` column is the Anki guid, so importing
+// the file again updates the notes instead of duplicating them.
+//
+// #separator:tab
+// #html:false
+// #guid column:1
+// #columns:id	hanzi	traditional	pinyin	definition	example
+// vocab-2118cbc109880ac8	学习	學習	xuéxí	to learn; to study	我们一起学习。
+use crate::laoshi::vocab::{Card, Deck};
+use chrono::NaiveDate;
+
+const COLUMNS: &[&str] = &[
+    "id",
+    "hanzi",
+    "traditional",
+    "pinyin",
+    "definition",
+    "example",
+];
+
+/// The columns of a TSV file without a `#columns:` header.
+const DEFAULT_IMPORT_COLUMNS: &[&str] =
+    &["hanzi", "pinyin", "definition", "example"];
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    /// Already in the deck (same id or hanzi), the SM-2 state is kept
+    pub updated: usize,
+    /// The lines without hanzi
+    pub skipped: usize,
+}
+
+impl Deck {
+    /// The deck as a TSV file (with the Anki headers).
+    pub fn to_tsv(&self) -> String {
+        let mut tsv = format!(
+            "#separator:tab\n#html:false\n#guid column:1\n#columns:{}\n",
+            COLUMNS.join("\t")
+        );
+        for card in &self.cards {
+            let fields = [
+                card.id.as_str(),
+                &card.simplified,
+                &card.traditional,
+                &card.pinyin,
+                &card.definition,
+                card.example.as_deref().unwrap_or_default(),
+            ];
+            let fields: Vec<String> = fields.iter().map(|f| tsv_field(f)).collect();
+            tsv.push_str(&fields.join("\t"));
+            tsv.push('\n');
+        }
+        tsv
+    }
+
+    /// Imports the cards of a TSV `content` (see `to_tsv`), added or updated
+    /// by id or hanzi. Without a `#columns:` header, the columns are
+    /// `hanzi pinyin definition example`.
+    pub fn import_tsv(&mut self, content: &str, today: NaiveDate) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+        let mut columns: Vec<String> = DEFAULT_IMPORT_COLUMNS
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+
+        for line in content.lines() {
+            if let Some(header) = line.strip_prefix("#columns:") {
+                columns = header
+                    .split('\t')
+                    .map(|c| c.trim().to_lowercase())
+                    .collect();
+                continue;
+            }
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            let values: Vec<&str> = line.split('\t').map(str::trim).collect();
+            let field = |names: &[&str]| -> &str {
+                columns
+                    .iter()
+                    .position(|c| names.contains(&c.as_str()))
+                    .and_then(|idx| values.get(idx).copied())
+                    .unwrap_or_default()
+            };
+            let id = field(&["id", "guid"]);
+            let hanzi = field(&["hanzi", "simplified"]);
+            let traditional = field(&["traditional"]);
+            let pinyin = field(&["pinyin"]);
+            let definition = field(&["definition", "meaning"]);
+            let example = field(&["example"]);
+            if hanzi.is_empty() {
+                summary.skipped += 1;
+                continue;
+            }
+
+            // -- Update (the non-empty fields)
+            let existing = self.cards.iter_mut().find(|card| {
+                (!id.is_empty() && card.id == id) || card.simplified == hanzi
+            });
+            if let Some(card) = existing {
+                for (value, dst) in [
+                    (traditional, &mut card.traditional),
+                    (pinyin, &mut card.pinyin),
+                    (definition, &mut card.definition),
+                ] {
+                    if !value.is_empty() {
+                        *dst = value.to_string();
+                    }
+                }
+                if !example.is_empty() {
+                    card.example = Some(example.to_string());
+                }
+                summary.updated += 1;
+                continue;
+            }
+
+            // -- Add (with the id of the file, if any)
+            let traditional = if traditional.is_empty() {
+                hanzi
+            } else {
+                traditional
+            };
+            let mut card = Card::new(hanzi, traditional, pinyin, definition, today);
+            if !id.is_empty() {
+                card.id = id.to_string();
+            }
+            card.example = (!example.is_empty()).then(|| example.to_string());
+            self.cards.push(card);
+            summary.added += 1;
+        }
+
+        summary
+    }
+}
+
+// region:       -- Support
+
+/// No tabs or new lines in a field.
+fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+// endregion:    -- Support
+
+// region:       -- Tests
+
+#[cfg(test)]
+mod tests {
+    type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+    use super::*;
+
+    #[test]
+    fn test_import_tsv_default_columns() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_today = NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("date")?;
+        let fx_tsv = "学习\txuéxí\tto learn\t我们一起学习。\n\n\txuéxí\tno hanzi\n";
+        let mut deck = Deck::default();
+
+        // -- Exec
+        let summary = deck.import_tsv(fx_tsv, fx_today);
+
+        // -- Check
+        assert_eq!((summary.added, summary.updated, summary.skipped), (1, 0, 1));
+        let card = &deck.cards()[0];
+        assert_eq!(card.simplified, "学习");
+        assert_eq!(card.traditional, "学习");
+        assert_eq!(card.pinyin, "xuéxí");
+        assert_eq!(card.definition, "to learn");
+        assert_eq!(card.example.as_deref(), Some("我们一起学习。"));
+        assert_eq!(card.due, fx_today);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_tsv_columns_header_updates() -> Result<()> {
+        // -- Setup & Fixtures
+        let fx_today = NaiveDate::from_ymd_opt(2024, 1, 1).ok_or("date")?;
+        let mut deck = Deck::default();
+        deck.add(Card::new("学习", "學習", "xuéxí", "to learn", fx_today));
+        deck.card_mut(&deck.cards()[0].id.clone())
+            .ok_or("card")?
+            .review(5, fx_today);
+        let fx_tsv =
+            "#separator:tab\n#columns:id\thanzi\ttraditional\tpinyin\tdefinition\n\
+            \t学习\t\t\tto study\n\
+            abc\t你好\t\tnǐhǎo\thello\n";
+
+        // -- Exec
+        let summary = deck.import_tsv(fx_tsv, fx_today);
+
+        // -- Check
+        assert_eq!((summary.added, summary.updated, summary.skipped), (1, 1, 0));
+        // updated: the non-empty fields, the SM-2 state kept
+        let card = &deck.cards()[0];
+        assert_eq!(card.traditional, "學習");
+        assert_eq!(card.definition, "to study");
+        assert_eq!(card.repetitions, 1);
+        // added: with the id of the file
+        let card = &deck.cards()[1];
+        assert_eq!(
+            (card.id.as_str(), card.simplified.as_str()),
+            ("abc", "你好")
+        );
+
+        // -- Exec & Check (the export imports back as updates)
+        let summary = deck.import_tsv(&deck.to_tsv(), fx_today);
+        assert_eq!((summary.added, summary.updated), (0, 2));
+
+        Ok(())
+    }
+}
+
+// endregion:    -- Tests
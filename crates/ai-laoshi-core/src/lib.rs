@@ -1,5 +1,6 @@
 // region:       -- Modules
 pub mod ais;
+pub mod chinese;
 mod error;
 mod laoshi;
 pub mod utils;
@@ -0,0 +1,93 @@
+// NOTE: Extracts the fenced code blocks (``` or ~~~) of a markdown text,
+// e.g., the laoshi answers, so the CLI/UI can list, save or pipe them.
+// Only fenced blocks are extracted (indented blocks are ambiguous in chat
+// answers), and fences nested in list items are supported.
+
+/// A fenced code block of a markdown text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    /// The first word of the info string (e.g., "rust"), if any
+    pub lang: Option<String>,
+    /// The code, with a trailing newline (empty if no lines)
+    pub code: String,
+}
+
+struct Fence {
+    char: char,
+    len: usize,
+    indent: usize,
+}
+
+/// The fenced code blocks of the `markdown`, in order.
+pub fn extract_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Fence, CodeBlock)> = None;
+
+    for line in markdown.lines() {
+        match current.as_mut() {
+            // -- In a block, until the closing fence
+            Some((fence, block)) => {
+                if is_closing_fence(line, fence) {
+                    if let Some((_, block)) = current.take() {
+                        blocks.push(block);
+                    }
+                } else {
+                    block.code.push_str(strip_indent(line, fence.indent));
+                    block.code.push('\n');
+                }
+            }
+            // -- Outside, look for an opening fence
+            None => {
+                if let Some((fence, lang)) = opening_fence(line) {
+                    let block = CodeBlock {
+                        lang,
+                        code: String::new(),
+                    };
+                    current = Some((fence, block));
+                }
+            }
+        }
+    }
+
+    // NOTE: An unclosed block runs until the end (like CommonMark)
+    if let Some((_, block)) = current {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+// region:       -- Support
+
+fn opening_fence(line: &str) -> Option<(Fence, Option<String>)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == char).count();
+    if len < 3 {
+        return None;
+    }
+
+    let info = trimmed[len..].trim();
+    // NOTE: A backtick fence info can't have backticks (inline code)
+    if char == '`' && info.contains('`') {
+        return None;
+    }
+    let lang = info.split_whitespace().next().map(String::from);
+
+    Some((Fence { char, len, indent }, lang))
+}
+
+fn is_closing_fence(line: &str, fence: &Fence) -> bool {
+    let trimmed = line.trim();
+    let len = trimmed.chars().take_while(|c| *c == fence.char).count();
+    len >= fence.len && len == trimmed.chars().count()
+}
+
+/// Removes up to `indent` leading spaces (the fence indentation).
+fn strip_indent(line: &str, indent: usize) -> &str {
+    let spaces = line.chars().take(indent).take_while(|c| *c == ' ').count();
+    &line[spaces..]
+}
+
+// endregion:    -- Support
@@ -0,0 +1,68 @@
+// NOTE: Frames a text document (e.g., piped stdin, a paste) into a chat
+// message, after the question. Documents larger than the limit are truncated
+// in the middle (the head and the tail are usually what matters, e.g., a diff
+// header or the last errors of a build log), with a notice for the model.
+
+/// The default max document chars in a message (the message limit is 32k).
+pub const MAX_DOCUMENT_CHARS: usize = 24_000;
+
+/// A document framed into a message.
+#[derive(Debug)]
+pub struct FramedDocument {
+    pub message: String,
+    /// The number of chars removed (0 if not truncated)
+    pub truncated_chars: usize,
+}
+
+/// Frames the `content` named `name` after the `question`.
+pub fn frame_document(
+    question: &str,
+    name: &str,
+    content: &str,
+    max_chars: usize,
+) -> FramedDocument {
+    let (content, truncated_chars) = truncate_middle(content.trim_end(), max_chars);
+
+    let mut message = String::new();
+    if !question.trim().is_empty() {
+        message.push_str(question.trim());
+        message.push_str("\n\n");
+    }
+    message.push_str(&format!(
+        "<document name=\"{name}\">\n{content}\n</document>"
+    ));
+
+    FramedDocument {
+        message,
+        truncated_chars,
+    }
+}
+
+/// Keeps the first 2/3 and the last 1/3 of `max_chars` (on line boundaries
+/// when possible), returning the removed char count.
+fn truncate_middle(content: &str, max_chars: usize) -> (String, usize) {
+    let total_chars = content.chars().count();
+    if total_chars <= max_chars {
+        return (content.to_string(), 0);
+    }
+
+    let head_chars = max_chars * 2 / 3;
+    let tail_chars = max_chars - head_chars;
+
+    let head: String = content.chars().take(head_chars).collect();
+    let head = match head.rfind('\n') {
+        Some(idx) if idx > 0 => head[..idx].to_string(),
+        _ => head,
+    };
+    let tail: String = content.chars().skip(total_chars - tail_chars).collect();
+    let tail = match tail.find('\n') {
+        Some(idx) if idx + 1 < tail.len() => tail[idx + 1..].to_string(),
+        _ => tail,
+    };
+
+    let removed = total_chars - head.chars().count() - tail.chars().count();
+    let notice =
+        format!("[... {removed} of {total_chars} characters truncated ...]");
+
+    (format!("{head}\n{notice}\n{tail}"), removed)
+}
@@ -1,27 +1,83 @@
 use crate::Result;
 use simple_fs::{get_buf_reader, SFile, SPath};
 use std::{
+    fmt::Write as _,
     fs::File,
     io::{BufRead, BufWriter, Write},
+    path::{Component, Path},
 };
 
+/// The line prefix delimiting each file inside a bundle file.
+pub const BUNDLE_FILE_DELIMITER: &str = "==== file path:";
+
 pub fn bundle_to_file(files: Vec<SFile>, dst_file: &SPath) -> Result<()> {
     let mut writer = BufWriter::new(File::create(dst_file)?);
+    writer.write_all(bundle_to_string(&files)?.as_bytes())?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Returns the bundle content (what `bundle_to_file` writes), so it can be
+/// hashed/compared without writing the bundle file.
+pub fn bundle_to_string(files: &[SFile]) -> Result<String> {
+    let mut content = String::new();
 
     for file in files {
-        let reader = get_buf_reader(&file)?;
+        let reader = get_buf_reader(file)?;
 
-        writeln!(writer, "\n// ==== file path: {file}\n")?;
+        let _ = writeln!(content, "\n// {BUNDLE_FILE_DELIMITER} {file}\n");
 
         for line in reader.lines() {
             let line = line?;
-            writeln!(writer, "{}", line)?;
+            let _ = writeln!(content, "{}", line);
         }
-        writeln!(writer, "\n\n")?;
+        let _ = writeln!(content, "\n\n");
     }
-    writer.flush()?;
 
-    Ok(())
+    Ok(content)
+}
+
+/// Returns an indented tree of the `files`, relative to `base_dir`.
+/// e.g.,
+/// ```text
+/// ai-laoshi-cli/
+///   src/
+///     main.rs
+/// ```
+pub fn files_tree(base_dir: &Path, files: &[SFile]) -> String {
+    let mut rel_paths: Vec<&Path> = files
+        .iter()
+        .filter_map(|f| f.path().strip_prefix(base_dir).ok())
+        .collect();
+    // NOTE: Path ordering is per component, so dirs stay grouped.
+    rel_paths.sort();
+
+    let mut tree = String::new();
+    let mut prev_dirs: Vec<Component> = Vec::new();
+    for rel_path in rel_paths {
+        let components: Vec<Component> = rel_path.components().collect();
+        let Some((file_name, dirs)) = components.split_last() else {
+            continue;
+        };
+
+        // Only print the dirs not already printed for the previous file
+        let common = prev_dirs
+            .iter()
+            .zip(dirs)
+            .take_while(|(prev, dir)| prev == dir)
+            .count();
+        for (depth, dir) in dirs.iter().enumerate().skip(common) {
+            let dir = dir.as_os_str().to_string_lossy();
+            let _ = writeln!(tree, "{}{dir}/", "  ".repeat(depth));
+        }
+        let file_name = file_name.as_os_str().to_string_lossy();
+        let _ = writeln!(tree, "{}{file_name}", "  ".repeat(dirs.len()));
+
+        prev_dirs = dirs.to_vec();
+    }
+
+    tree
 }
 
 // pub fn bundle_to_file_2(
@@ -0,0 +1,8 @@
+use sha2::{Digest, Sha256};
+
+/// Returns the lowercase hex SHA-256 of the `content`.
+// NOTE: Used to detect local changes (instructions, bundles) without
+// having to download anything from OpenAI.
+pub fn sha256_hex(content: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(content.as_ref()))
+}
@@ -0,0 +1,18 @@
+use serde::de::DeserializeOwned;
+
+/// Parses the JSON object of a model response (as is, or between the first
+/// `{` and the last `}`, e.g., in a ```json fence).
+// NOTE: The retrieval assistants can't use the JSON response format, so the
+// JSON is asked in the message and the model may still wrap it in text.
+pub fn extract_json<T: DeserializeOwned>(res: &str) -> Option<T> {
+    let res = res.trim();
+    if let Ok(value) = serde_json::from_str(res) {
+        return Some(value);
+    }
+    let start = res.find('{')?;
+    let end = res.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&res[start..=end]).ok()
+}
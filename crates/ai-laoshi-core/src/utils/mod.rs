@@ -5,6 +5,10 @@
 
 // region:       -- Modules
 
+pub mod code_blocks;
+pub mod document;
 pub mod files;
+pub mod hash;
+pub mod json;
 
 // region:       -- Modules